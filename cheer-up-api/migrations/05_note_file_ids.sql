@@ -0,0 +1,7 @@
+ALTER TABLE notes ADD COLUMN file_id TEXT;
+
+ALTER TABLE notes ADD COLUMN file_unique_id TEXT;
+
+ALTER TABLE notes ADD COLUMN duration INTEGER;
+
+ALTER TABLE notes ADD COLUMN file_size INTEGER;
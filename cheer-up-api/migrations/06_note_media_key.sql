@@ -0,0 +1,18 @@
+-- INFO: key of the note file inside the configured media store,
+--       existing notes keep the `<telegram_id>_<username>/<file_name>` folder layout.
+--       Users without a telegram username were saved with their telegram id as
+--       username, while their files went to `<telegram_id>_none/`
+ALTER TABLE notes ADD COLUMN media_key TEXT;
+
+UPDATE notes
+SET media_key = (
+    SELECT users.telegram_id || '_' || (
+        CASE
+            WHEN users.username = CAST(users.telegram_id AS TEXT) THEN 'none'
+            ELSE users.username
+        END
+    ) || '/' || notes.file_name
+    FROM users
+    WHERE users.id = notes.user_id
+)
+WHERE media_key IS NULL;
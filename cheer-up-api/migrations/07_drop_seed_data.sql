@@ -0,0 +1,9 @@
+-- INFO: demo users and notes are now loaded by `--seed` from `fixtures/seed.toml`,
+--       remove the rows seeded by 03_users.sql/04_notes.sql
+DELETE FROM notes
+WHERE user_id IN (
+    SELECT id FROM users WHERE telegram_id IN (1234567, 8234541)
+);
+
+DELETE FROM users
+WHERE telegram_id IN (1234567, 8234541);
@@ -0,0 +1,46 @@
+CREATE TABLE IF NOT EXISTS groups (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    invite_code TEXT NOT NULL,
+    created_by INTEGER,
+    UNIQUE(invite_code),
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS group_members (
+    group_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    PRIMARY KEY (group_id, user_id),
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- INFO: NULL means the note is shared with every group its author belongs to
+ALTER TABLE notes ADD COLUMN group_id INTEGER REFERENCES groups(id) ON DELETE CASCADE;
+
+-- INFO: who can receive each note, single place encoding group visibility rules
+CREATE VIEW IF NOT EXISTS note_audience AS
+SELECT n.id AS note_id, gm.user_id AS user_id
+FROM notes n
+INNER JOIN group_members gm ON gm.group_id = n.group_id
+UNION
+SELECT n.id AS note_id, viewer.user_id AS user_id
+FROM notes n
+INNER JOIN group_members author ON author.user_id = n.user_id
+INNER JOIN group_members viewer ON viewer.group_id = author.group_id
+WHERE n.group_id IS NULL;
+
+CREATE VIEW IF NOT EXISTS group_peers AS
+SELECT DISTINCT a.user_id AS user_id, b.user_id AS peer_id
+FROM group_members a
+INNER JOIN group_members b ON a.group_id = b.group_id;
+
+-- INFO: existing deployments were a single global pool, keep it as a default group
+INSERT INTO groups (name, invite_code)
+SELECT 'everyone', lower(hex(randomblob(5)))
+WHERE EXISTS (SELECT 1 FROM users);
+
+INSERT INTO group_members (group_id, user_id)
+SELECT g.id, u.id
+FROM users u
+INNER JOIN groups g ON g.name = 'everyone';
@@ -0,0 +1,20 @@
+-- INFO: NULL means the note is for everyone in its groups, otherwise only the recipient gets it
+ALTER TABLE notes ADD COLUMN recipient_id INTEGER REFERENCES users(id) ON DELETE CASCADE;
+
+DROP VIEW IF EXISTS note_audience;
+
+CREATE VIEW note_audience AS
+SELECT n.id AS note_id, gm.user_id AS user_id
+FROM notes n
+INNER JOIN group_members gm ON gm.group_id = n.group_id
+WHERE n.recipient_id IS NULL
+UNION
+SELECT n.id AS note_id, viewer.user_id AS user_id
+FROM notes n
+INNER JOIN group_members author ON author.user_id = n.user_id
+INNER JOIN group_members viewer ON viewer.group_id = author.group_id
+WHERE n.group_id IS NULL AND n.recipient_id IS NULL
+UNION
+SELECT n.id AS note_id, n.recipient_id AS user_id
+FROM notes n
+WHERE n.recipient_id IS NOT NULL;
@@ -0,0 +1,12 @@
+-- INFO: one row per delivered note, drives the non-repeating random selection
+CREATE TABLE IF NOT EXISTS note_views (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    note_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    -- INFO: millisecond precision so notes served in the same second keep their order
+    viewed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f', 'now')),
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS note_views_user_note ON note_views (user_id, note_id, viewed_at);
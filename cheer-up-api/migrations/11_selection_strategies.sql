@@ -0,0 +1,15 @@
+-- INFO: upload time, NULL for notes uploaded before it was recorded
+ALTER TABLE notes ADD COLUMN created_at TEXT;
+
+-- INFO: per user random selection strategy, NULL falls back to the deployment default
+ALTER TABLE users ADD COLUMN selection_strategy TEXT;
+
+-- INFO: notes a user saved, also used to weight the `favorites` selection strategy
+CREATE TABLE IF NOT EXISTS favorites (
+    user_id INTEGER NOT NULL,
+    note_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f', 'now')),
+    PRIMARY KEY (user_id, note_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+);
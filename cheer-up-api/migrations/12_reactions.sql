@@ -0,0 +1,10 @@
+-- INFO: a single reaction per user and note, reacting again replaces it
+CREATE TABLE IF NOT EXISTS reactions (
+    user_id INTEGER NOT NULL,
+    note_id INTEGER NOT NULL,
+    reaction TEXT NOT NULL CHECK (reaction IN ('heart', 'laugh', 'cry')),
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f', 'now')),
+    PRIMARY KEY (user_id, note_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+-- INFO: opt-in digests telling authors how friends received their notes,
+--       users without a row are muted
+CREATE TABLE IF NOT EXISTS digest_settings (
+    user_id INTEGER PRIMARY KEY,
+    muted BOOLEAN NOT NULL DEFAULT 1,
+    -- INFO: end of the period covered by the last digest, the next one starts here
+    last_sent_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f', 'now')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,15 @@
+-- INFO: automatic random note deliveries, a single schedule per user
+CREATE TABLE IF NOT EXISTS subscriptions (
+    user_id INTEGER PRIMARY KEY,
+    frequency TEXT NOT NULL CHECK (frequency IN ('daily', 'weekly')),
+    -- INFO: `HH:MM` in `timezone`, weekly deliveries also need `weekday` (0 is monday)
+    local_time TEXT NOT NULL,
+    timezone TEXT NOT NULL,
+    weekday INTEGER CHECK (weekday BETWEEN 0 AND 6),
+    -- INFO: UTC `%Y-%m-%d %H:%M:%S`, computed by the bot from the local schedule
+    next_delivery_at TEXT NOT NULL,
+    last_delivered_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS subscriptions_next_delivery_at ON subscriptions (next_delivery_at);
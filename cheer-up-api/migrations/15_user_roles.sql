@@ -0,0 +1,2 @@
+-- INFO: admins can delete any note, everyone else only their own
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'user' CHECK (role IN ('user', 'admin'));
@@ -0,0 +1,102 @@
+-- INFO: users were looked up by username, so telegram accounts that changed or lacked
+--       one were registered again. The oldest row of each account is kept, with the
+--       names of the newest one, and everything owned by the duplicates moves to it
+CREATE TEMP TABLE user_duplicates AS
+SELECT u.id AS duplicate_id, k.kept_id
+FROM users u
+INNER JOIN (
+    SELECT telegram_id, MIN(id) AS kept_id
+    FROM users
+    GROUP BY telegram_id
+    HAVING COUNT(*) > 1
+) k ON k.telegram_id = u.telegram_id
+WHERE u.id != k.kept_id;
+
+CREATE TEMP TABLE user_latest_names AS
+SELECT k.kept_id, u.username, u.first_name, u.last_name
+FROM users u
+INNER JOIN (
+    SELECT d.kept_id, MAX(d.duplicate_id) AS latest_id
+    FROM user_duplicates d
+    GROUP BY d.kept_id
+) k ON k.latest_id = u.id;
+
+UPDATE notes
+SET user_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = notes.user_id)
+WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE notes
+SET recipient_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = notes.recipient_id)
+WHERE recipient_id IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE groups
+SET created_by = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = groups.created_by)
+WHERE created_by IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE note_views
+SET user_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = note_views.user_id)
+WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+-- INFO: rows keyed on the user already present for the kept one are left behind
+--       and deleted with the duplicates below
+UPDATE OR IGNORE group_members
+SET user_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = group_members.user_id)
+WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE OR IGNORE reactions
+SET user_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = reactions.user_id)
+WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE OR IGNORE favorites
+SET user_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = favorites.user_id)
+WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE OR IGNORE digest_settings
+SET user_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = digest_settings.user_id)
+WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE OR IGNORE subscriptions
+SET user_id = (SELECT kept_id FROM user_duplicates WHERE duplicate_id = subscriptions.user_id)
+WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+DELETE FROM group_members WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+DELETE FROM reactions WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+DELETE FROM favorites WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+DELETE FROM digest_settings WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+DELETE FROM subscriptions WHERE user_id IN (SELECT duplicate_id FROM user_duplicates);
+
+-- INFO: the kept row stays admin when any duplicate was, and takes the strategy
+--       of the newest duplicate that chose one when it has none itself
+UPDATE users
+SET role = 'admin'
+WHERE id IN (
+    SELECT d.kept_id
+    FROM user_duplicates d
+    INNER JOIN users u ON u.id = d.duplicate_id
+    WHERE u.role = 'admin'
+);
+
+UPDATE users
+SET selection_strategy = (
+    SELECT u.selection_strategy
+    FROM user_duplicates d
+    INNER JOIN users u ON u.id = d.duplicate_id
+    WHERE d.kept_id = users.id AND u.selection_strategy IS NOT NULL
+    ORDER BY d.duplicate_id DESC
+    LIMIT 1
+)
+WHERE selection_strategy IS NULL
+  AND id IN (SELECT kept_id FROM user_duplicates);
+
+DELETE FROM users
+WHERE id IN (SELECT duplicate_id FROM user_duplicates);
+
+UPDATE users
+SET username = l.username, first_name = l.first_name, last_name = l.last_name
+FROM user_latest_names l
+WHERE users.id = l.kept_id;
+
+DROP TABLE user_duplicates;
+DROP TABLE user_latest_names;
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_telegram_id ON users (telegram_id);
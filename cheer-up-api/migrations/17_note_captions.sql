@@ -0,0 +1,2 @@
+-- INFO: optional text written by the author, shown next to the note
+ALTER TABLE notes ADD COLUMN caption TEXT;
@@ -0,0 +1,8 @@
+-- INFO: step of each chat in the multi-step flows of the manager bot, `state` is
+--       JSON only the bot reads. Kept here so that flows survive bot restarts
+CREATE TABLE IF NOT EXISTS dialogues (
+    chat_id INTEGER PRIMARY KEY,
+    state TEXT NOT NULL,
+    -- INFO: UTC `%Y-%m-%d %H:%M:%S`
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%S', 'now'))
+);
@@ -0,0 +1,67 @@
+-- INFO: `notes.user_id` had no ON DELETE action, so users with notes could not be
+--       deleted. SQLite cannot change a foreign key in place, the table is rebuilt
+--       with the same columns, ids and autoincrement sequence
+
+-- INFO: migrations run in a transaction with foreign keys on, dropping `notes`
+--       deletes the rows referencing it, they are kept aside and put back
+CREATE TEMP TABLE kept_note_views AS SELECT * FROM note_views;
+CREATE TEMP TABLE kept_favorites AS SELECT * FROM favorites;
+CREATE TEMP TABLE kept_reactions AS SELECT * FROM reactions;
+CREATE TEMP TABLE kept_notes_seq AS SELECT seq FROM sqlite_sequence WHERE name = 'notes';
+
+-- INFO: renaming the new table fails while a view refers to a missing `notes`
+DROP VIEW IF EXISTS note_audience;
+
+CREATE TABLE notes_rebuilt (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    file_name TEXT NOT NULL,
+    file_id TEXT,
+    file_unique_id TEXT,
+    duration INTEGER,
+    file_size INTEGER,
+    media_key TEXT,
+    group_id INTEGER REFERENCES groups(id) ON DELETE CASCADE,
+    recipient_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    created_at TEXT,
+    caption TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+INSERT INTO notes_rebuilt (id, user_id, file_name, file_id, file_unique_id, duration, file_size, media_key, group_id, recipient_id, created_at, caption)
+SELECT id, user_id, file_name, file_id, file_unique_id, duration, file_size, media_key, group_id, recipient_id, created_at, caption
+FROM notes;
+
+DROP TABLE notes;
+
+ALTER TABLE notes_rebuilt RENAME TO notes;
+
+-- INFO: ids of deleted notes are not handed out again, old buttons may still carry them
+UPDATE sqlite_sequence
+SET seq = MAX(seq, (SELECT seq FROM kept_notes_seq))
+WHERE name = 'notes' AND EXISTS (SELECT 1 FROM kept_notes_seq);
+
+INSERT INTO note_views SELECT * FROM kept_note_views;
+INSERT INTO favorites SELECT * FROM kept_favorites;
+INSERT INTO reactions SELECT * FROM kept_reactions;
+
+DROP TABLE kept_note_views;
+DROP TABLE kept_favorites;
+DROP TABLE kept_reactions;
+DROP TABLE kept_notes_seq;
+
+CREATE VIEW note_audience AS
+SELECT n.id AS note_id, gm.user_id AS user_id
+FROM notes n
+INNER JOIN group_members gm ON gm.group_id = n.group_id
+WHERE n.recipient_id IS NULL
+UNION
+SELECT n.id AS note_id, viewer.user_id AS user_id
+FROM notes n
+INNER JOIN group_members author ON author.user_id = n.user_id
+INNER JOIN group_members viewer ON viewer.group_id = author.group_id
+WHERE n.group_id IS NULL AND n.recipient_id IS NULL
+UNION
+SELECT n.id AS note_id, n.recipient_id AS user_id
+FROM notes n
+WHERE n.recipient_id IS NOT NULL;
@@ -1,4 +1,165 @@
+use std::{net::SocketAddr, path::PathBuf};
+
+use cheer_up_core::config::{read_toml, required, ConfigError, MediaArgs, MediaFile};
+use cheer_up_core::media::MediaConfig;
+use cheer_up_core::note::SelectionStrategy;
+use clap::{Args, ValueEnum};
+use serde::Deserialize;
+
+use crate::http::auth::ApiKeys;
+
+pub const DEFAULT_DATABASE_URL: &str = "sqlite://../_common_data/data/database.db?mode=rwc";
+pub const DEFAULT_HOST: &str = "0.0.0.0";
+pub const DEFAULT_PORT: u16 = 1989;
+pub const DEFAULT_MEDIA_ROOT: &str = "../_common_data/videonotes";
+pub const DEFAULT_FIXTURES_FOLDER: &str = "fixtures";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Environment {
+    #[default]
+    Development,
+    /// Refuses development helpers such as `--seed`
+    Production,
+}
+
 #[derive(Debug)]
 pub struct Config {
-    database_url: String,
+    pub database_url: String,
+    pub listen_addr: SocketAddr,
+    pub environment: Environment,
+    /// Used for users without a strategy of their own
+    pub selection_strategy: SelectionStrategy,
+    pub api_keys: ApiKeys,
+    pub media: MediaConfig,
+    /// Folder holding `seed.toml`, set only when seeding was requested
+    pub seed: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    database_url: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    environment: Option<Environment>,
+    selection_strategy: Option<SelectionStrategy>,
+    bot_api_key: Option<String>,
+    manager_api_key: Option<String>,
+    fixtures: Option<PathBuf>,
+    media: MediaFile,
+}
+
+/// Api cli flags, every flag can also be set through the env variable
+/// shown in `--help` (cli flag > env variable > config file > default).
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    /// Path to the TOML config file
+    #[arg(long, env = "CHEER_UP_API_CONFIG", default_value = "config.toml")]
+    pub config: PathBuf,
+    /// SQLite connection url
+    #[arg(long, env = "DATABASE_URL")]
+    pub database_url: Option<String>,
+    /// Address the http server binds to
+    #[arg(long, env = "CHEER_UP_API_HOST")]
+    pub host: Option<String>,
+    /// Port the http server listens on
+    #[arg(long, env = "CHEER_UP_API_PORT")]
+    pub port: Option<u16>,
+    /// Deployment environment
+    #[arg(long = "env", env = "CHEER_UP_API_ENV", value_enum)]
+    pub environment: Option<Environment>,
+    /// Default way of picking random notes
+    #[arg(long, env = "CHEER_UP_API_SELECTION_STRATEGY", value_enum)]
+    pub selection_strategy: Option<SelectionStrategy>,
+    /// Api key of `cheer-up`, read access plus the writes its users trigger
+    #[arg(long, env = "CHEER_UP_API_BOT_KEY", hide_env_values = true)]
+    pub bot_api_key: Option<String>,
+    /// Api key of `cheer-up-manager`, full access
+    #[arg(long, env = "CHEER_UP_API_MANAGER_KEY", hide_env_values = true)]
+    pub manager_api_key: Option<String>,
+    /// Load users and notes from `<fixtures>/seed.toml` at startup (development only)
+    #[arg(long, env = "CHEER_UP_API_SEED")]
+    pub seed: bool,
+    /// Folder holding seed fixtures
+    #[arg(long, env = "CHEER_UP_API_FIXTURES")]
+    pub fixtures: Option<PathBuf>,
+    #[command(flatten)]
+    pub media: MediaArgs,
+}
+
+impl Config {
+    pub fn load(args: ConfigArgs) -> Result<Config, ConfigError> {
+        let file: ConfigFile = read_toml(&args.config)?;
+
+        let database_url = args
+            .database_url
+            .or(file.database_url)
+            .unwrap_or(DEFAULT_DATABASE_URL.to_string());
+        if !database_url.starts_with("sqlite:") {
+            return Err(ConfigError::Invalid {
+                key: "database_url",
+                reason: format!("{:?} is not a sqlite connection url", database_url),
+            });
+        }
+
+        let host = args.host.or(file.host).unwrap_or(DEFAULT_HOST.to_string());
+        let port = args.port.or(file.port).unwrap_or(DEFAULT_PORT);
+
+        let listen_addr = format!("{}:{}", host, port)
+            .parse::<SocketAddr>()
+            .map_err(|e| ConfigError::Invalid {
+                key: "host",
+                reason: format!("{:?} is not a valid ip address ({})", host, e),
+            })?;
+
+        let environment = args.environment.or(file.environment).unwrap_or_default();
+
+        let selection_strategy = args
+            .selection_strategy
+            .or(file.selection_strategy)
+            .unwrap_or_default();
+
+        let api_keys = ApiKeys {
+            bot: required("bot_api_key", args.bot_api_key, file.bot_api_key)?,
+            manager: required(
+                "manager_api_key",
+                args.manager_api_key,
+                file.manager_api_key,
+            )?,
+        };
+        if api_keys.bot == api_keys.manager {
+            return Err(ConfigError::Invalid {
+                key: "bot_api_key",
+                reason: "must differ from manager_api_key".to_string(),
+            });
+        }
+
+        let seed = match args.seed {
+            true if environment == Environment::Production => {
+                return Err(ConfigError::Invalid {
+                    key: "seed",
+                    reason: "seed data cannot be loaded in production".to_string(),
+                })
+            }
+            true => Some(
+                args.fixtures
+                    .or(file.fixtures)
+                    .unwrap_or(PathBuf::from(DEFAULT_FIXTURES_FOLDER)),
+            ),
+            false => None,
+        };
+
+        let media = MediaConfig::load(args.media, file.media, PathBuf::from(DEFAULT_MEDIA_ROOT))?;
+
+        Ok(Config {
+            database_url,
+            listen_addr,
+            environment,
+            selection_strategy,
+            api_keys,
+            media,
+            seed,
+        })
+    }
 }
@@ -0,0 +1,266 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use cheer_up_core::media::{parse_media_key, GcReport, MediaEntry, MediaStore};
+use chrono::{DateTime, TimeDelta, Utc};
+use log::{info, warn};
+use sqlx::SqlitePool;
+
+use crate::media_keys::{stored_notes, StoredNote};
+
+/// How often the media store and the `notes` table are compared.
+pub const GC_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Age under which a file is kept even if no note refers to it, the manager
+/// stores the file of a note right before saving the note.
+pub const GC_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Collects garbage every [`GC_INTERVAL`] until the api stops, spawned next to
+/// the http server. The first pass runs one interval after startup.
+pub async fn run(pool: SqlitePool, store: Arc<dyn MediaStore>) {
+    let mut interval = tokio::time::interval(GC_INTERVAL);
+    // INFO: the first tick completes immediately, restarts should not trigger a pass
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = collect(&pool, store.as_ref(), Utc::now()).await {
+            warn!("[GC] could not collect media garbage: {}", e);
+        }
+    }
+}
+
+/// Deletes the note files of `store` no note refers to anymore, and reports
+/// the notes whose file is missing. Those are only reported, they can still
+/// be sent by `file_id` by the bot that uploaded them.
+pub async fn collect(
+    pool: &SqlitePool,
+    store: &dyn MediaStore,
+    now: DateTime<Utc>,
+) -> anyhow::Result<GcReport> {
+    let notes = stored_notes(pool).await?;
+    let entries = store.list("").await?;
+    let cutoff = now - TimeDelta::from_std(GC_GRACE_PERIOD)?;
+
+    let (garbage, missing) = compare(&entries, &notes, cutoff);
+
+    let mut report = GcReport {
+        missing,
+        ..GcReport::default()
+    };
+
+    for entry in garbage {
+        if let Err(e) = store.delete(&entry.key).await {
+            warn!("[GC] could not delete {}: {}", entry.key, e);
+            continue;
+        }
+
+        report.reclaimed_bytes += entry.size;
+        report.removed.push(entry.key.clone());
+    }
+
+    info!(
+        "[GC] removed {} files, {} bytes reclaimed",
+        report.removed.len(),
+        report.reclaimed_bytes
+    );
+    if !report.missing.is_empty() {
+        warn!("[GC] notes with a missing file: {:?}", report.missing);
+    }
+
+    Ok(report)
+}
+
+/// Deletes the files of notes just removed from the database. Failures are
+/// only logged, the file is collected by the next pass.
+pub async fn delete_note_media(store: &dyn MediaStore, media_keys: &[String]) {
+    for key in media_keys {
+        if let Err(e) = store.delete(key).await {
+            warn!("[DELETE_NOTE_MEDIA] could not delete {}: {}", key, e);
+        }
+    }
+}
+
+/// Splits `entries` and `notes` into the files to delete and the notes whose
+/// file is missing.
+fn compare<'a>(
+    entries: &'a [MediaEntry],
+    notes: &[StoredNote],
+    cutoff: DateTime<Utc>,
+) -> (Vec<&'a MediaEntry>, Vec<i64>) {
+    let stored: HashSet<&str> = entries.iter().map(|entry| entry.key.as_str()).collect();
+    // INFO: a file is kept while a note of its author has its name, whatever the
+    //       folder: keys of old notes may name another username than the one stored
+    let referenced: HashSet<(i64, &str)> = notes
+        .iter()
+        .map(|note| (note.telegram_id, note.file_name.as_str()))
+        .collect();
+    let referenced_keys: HashSet<&str> = notes.iter().map(|note| note.media_key.as_str()).collect();
+
+    let missing = notes
+        .iter()
+        .filter(|note| !stored.contains(note.media_key.as_str()))
+        .map(|note| note.id)
+        .collect();
+
+    // INFO: no note with a file more likely means a wrong `database_url` than
+    //       every note deleted, the store is left alone
+    if referenced.is_empty() {
+        return (vec![], missing);
+    }
+
+    let garbage = entries
+        .iter()
+        .filter(|entry| !referenced_keys.contains(entry.key.as_str()))
+        .filter(|entry| parse_media_key(&entry.key).is_some_and(|file| !referenced.contains(&file)))
+        .filter(|entry| entry.last_modified.is_some_and(|date| date < cutoff))
+        .collect();
+
+    (garbage, missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use cheer_up_core::media::LocalMediaStore;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    fn entry(key: &str, size: u64, hours_ago: i64) -> MediaEntry {
+        MediaEntry {
+            key: key.to_string(),
+            size,
+            last_modified: Some(Utc::now() - TimeDelta::try_hours(hours_ago).unwrap()),
+        }
+    }
+
+    fn note(id: i64, media_key: &str) -> StoredNote {
+        let (telegram_id, file_name) = parse_media_key(media_key).unwrap();
+
+        StoredNote {
+            id,
+            telegram_id,
+            file_name: file_name.to_string(),
+            media_key: media_key.to_string(),
+        }
+    }
+
+    #[test]
+    fn only_old_unreferenced_note_files_are_garbage() {
+        let entries = vec![
+            entry("1_ana/kept.mpeg", 10, 48),
+            entry("1_ana/deleted.mpeg", 20, 48),
+            entry("1_ana/just_uploaded.mpeg", 30, 0),
+            entry("2_bob/deleted.mpeg", 40, 48),
+            entry("backups/database.db", 50, 48),
+            MediaEntry {
+                last_modified: None,
+                ..entry("2_bob/unknown_age.mpeg", 60, 48)
+            },
+        ];
+        let notes = vec![note(1, "1_ana/kept.mpeg"), note(2, "1_ana/lost.mpeg")];
+
+        let (garbage, missing) = compare(
+            &entries,
+            &notes,
+            Utc::now() - TimeDelta::try_hours(1).unwrap(),
+        );
+
+        let garbage: Vec<&str> = garbage.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(garbage, vec!["1_ana/deleted.mpeg", "2_bob/deleted.mpeg"]);
+        assert_eq!(missing, vec![2]);
+    }
+
+    #[test]
+    fn files_of_existing_notes_are_kept_in_any_folder() {
+        let entries = vec![
+            entry("3_none/nameless.mpeg", 10, 48),
+            entry("4_old_name/renamed.mpeg", 20, 48),
+            entry("4_old_name/deleted.mpeg", 30, 48),
+        ];
+        let notes = vec![
+            note(1, "3_3/nameless.mpeg"),
+            note(2, "4_new_name/renamed.mpeg"),
+        ];
+
+        let (garbage, missing) = compare(
+            &entries,
+            &notes,
+            Utc::now() - TimeDelta::try_hours(1).unwrap(),
+        );
+
+        let garbage: Vec<&str> = garbage.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(garbage, vec!["4_old_name/deleted.mpeg"]);
+        assert_eq!(missing, vec![1, 2]);
+    }
+
+    #[test]
+    fn nothing_is_collected_without_referenced_files() {
+        let entries = vec![entry("1_ana/deleted.mpeg", 20, 48)];
+
+        let (garbage, missing) = compare(&entries, &[], Utc::now());
+
+        assert!(garbage.is_empty());
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn recognizes_note_media_keys() {
+        assert_eq!(
+            parse_media_key("123_ana/vnote_1.mpeg"),
+            Some((123, "vnote_1.mpeg"))
+        );
+        assert_eq!(
+            parse_media_key("123_ana_maria/vnote_1.mpeg"),
+            Some((123, "vnote_1.mpeg"))
+        );
+
+        assert_eq!(parse_media_key("vnote_1.mpeg"), None);
+        assert_eq!(parse_media_key("ana_123/vnote_1.mpeg"), None);
+        assert_eq!(parse_media_key("123_/vnote_1.mpeg"), None);
+        assert_eq!(parse_media_key("123_ana/nested/vnote_1.mpeg"), None);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_file_of_a_legacy_author_without_username() {
+        // INFO: a single connection, every connection to `:memory:` is a new database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        // INFO: saved with the telegram id as username, the file went to `<telegram_id>_none/`
+        let user_id = sqlx::query_scalar!(
+            r#"
+INSERT INTO users (telegram_id, username, first_name, locale)
+VALUES (300, '300', '300', (SELECT id FROM locales WHERE language = 'en'))
+RETURNING id
+            "#
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO notes (user_id, file_name, media_key) VALUES (?, 'legacy.mpeg', '300_300/legacy.mpeg')",
+            user_id
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let root = std::env::temp_dir().join(format!("cheer-up-gc-tests-{}", std::process::id()));
+        let store = LocalMediaStore::new(&root);
+        store.put("300_none/legacy.mpeg", vec![1]).await.unwrap();
+        store.put("300_none/deleted.mpeg", vec![2]).await.unwrap();
+
+        // INFO: far enough in the future for both files to be past the grace period
+        let later = Utc::now() + TimeDelta::try_days(2).unwrap();
+        let report = collect(&pool, &store, later).await.unwrap();
+
+        assert_eq!(report.removed, vec!["300_none/deleted.mpeg"]);
+        assert!(store.exists("300_none/legacy.mpeg").await.unwrap());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use cheer_up_core::client::API_KEY_HEADER;
+use log::info;
+
+use crate::http::error::Error;
+use crate::http::http::Result;
+
+/// Keys of the services allowed to call the api.
+#[derive(Debug, Clone)]
+pub struct ApiKeys {
+    /// `cheer-up`, reads plus the writes its users trigger
+    pub bot: String,
+    /// `cheer-up-manager`, full access
+    pub manager: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    Bot,
+    Manager,
+}
+
+impl ApiKeys {
+    pub fn service(&self, key: &str) -> Option<Service> {
+        if constant_time_eq(key.as_bytes(), self.manager.as_bytes()) {
+            Some(Service::Manager)
+        } else if constant_time_eq(key.as_bytes(), self.bot.as_bytes()) {
+            Some(Service::Bot)
+        } else {
+            None
+        }
+    }
+}
+
+/// Rejects requests without a known key with 401, and bot requests to
+/// manager-only routes with 403.
+pub async fn require_api_key(
+    State(keys): State<ApiKeys>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let service = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|key| key.to_str().ok())
+        .and_then(|key| keys.service(key))
+        .ok_or(Error::Unauthorized)?;
+
+    // INFO: `route_layer` only runs on matched routes, the path is always there
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str())
+        .unwrap_or_default();
+
+    if !is_allowed(service, request.method(), route) {
+        info!(
+            "[AUTH] {:?} is not allowed to {} {}",
+            service,
+            request.method(),
+            route
+        );
+        return Err(Error::Forbidden);
+    }
+
+    Ok(next.run(request).await)
+}
+
+pub fn is_allowed(service: Service, method: &Method, route: &str) -> bool {
+    match service {
+        Service::Manager => true,
+        Service::Bot if method == Method::GET => true,
+        // INFO: what `cheer-up` users can do, bulk deletions and admin
+        //       writes stay with the manager
+        Service::Bot => matches!(
+            (method.as_str(), route),
+            ("POST", "/api/users" | "/api/users/")
+                | ("PUT", "/api/users/telegram/:telegram_id")
+                | ("PATCH", "/api/locale/:user_id")
+                | ("POST", "/api/groups" | "/api/groups/" | "/api/groups/join")
+                | ("POST", "/api/notes" | "/api/notes/")
+                | ("DELETE", "/api/notes/:note_id")
+                | ("POST", "/api/notes/:note_id/views")
+                | ("POST", "/api/notes/:note_id/reactions")
+                | ("POST", "/api/users/:user_id/favorites")
+                | ("PUT" | "DELETE", "/api/subscriptions/:user_id")
+                | ("POST", "/api/subscriptions/:user_id/delivered")
+        ),
+    }
+}
+
+// INFO: compares every byte so that response times do not leak key prefixes
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_map_to_their_service() {
+        let keys = ApiKeys {
+            bot: "bot-key".to_string(),
+            manager: "manager-key".to_string(),
+        };
+
+        assert_eq!(keys.service("bot-key"), Some(Service::Bot));
+        assert_eq!(keys.service("manager-key"), Some(Service::Manager));
+        assert_eq!(keys.service("bot-ke"), None);
+        assert_eq!(keys.service(""), None);
+    }
+
+    #[test]
+    fn bot_is_read_mostly() {
+        assert!(is_allowed(Service::Bot, &Method::GET, "/api/users"));
+        assert!(is_allowed(
+            Service::Bot,
+            &Method::POST,
+            "/api/notes/:note_id/reactions"
+        ));
+
+        assert!(!is_allowed(
+            Service::Bot,
+            &Method::DELETE,
+            "/api/users/:user_id"
+        ));
+        assert!(!is_allowed(
+            Service::Bot,
+            &Method::DELETE,
+            "/api/notes/user/:user_id"
+        ));
+        assert!(!is_allowed(Service::Bot, &Method::DELETE, "/api/notes"));
+        assert!(!is_allowed(Service::Bot, &Method::POST, "/api/media/gc"));
+        assert!(!is_allowed(
+            Service::Bot,
+            &Method::POST,
+            "/api/digests/:user_id/sent"
+        ));
+        assert!(!is_allowed(
+            Service::Bot,
+            &Method::PUT,
+            "/api/dialogues/:chat_id"
+        ));
+
+        assert!(is_allowed(
+            Service::Manager,
+            &Method::DELETE,
+            "/api/users/:user_id"
+        ));
+    }
+}
@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Router,
+};
+use cheer_up_core::dialogue::{Dialogue, DialogueBody, NewDialogue};
+use sqlx::SqlitePool;
+
+use crate::http::error::{Error, Json, Resource};
+use crate::http::http::Result;
+
+pub fn router(pool: SqlitePool) -> Router<()> {
+    Router::new()
+        .route(
+            "/api/dialogues/:chat_id",
+            get(get_dialogue).put(set_dialogue).delete(delete_dialogue),
+        )
+        .with_state(pool)
+}
+
+async fn get_dialogue(
+    Path(chat_id): Path<i64>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<DialogueBody<Dialogue>>> {
+    let dialogue = sqlx::query_as!(
+        Dialogue,
+        r#"
+SELECT chat_id AS "chat_id!: i64", state, updated_at
+FROM dialogues
+WHERE chat_id = ?
+    "#,
+        chat_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::Dialogue))?;
+
+    Ok(Json(DialogueBody { dialogue }))
+}
+
+async fn set_dialogue(
+    Path(chat_id): Path<i64>,
+    State(pool): State<SqlitePool>,
+    Json(dialogue): Json<NewDialogue>,
+) -> Result<Json<DialogueBody<Dialogue>>> {
+    sqlx::query!(
+        r#"
+INSERT INTO dialogues (chat_id, state)
+VALUES (?1, ?2)
+ON CONFLICT (chat_id) DO UPDATE
+SET state = excluded.state,
+    updated_at = strftime('%Y-%m-%d %H:%M:%S', 'now')
+    "#,
+        chat_id,
+        dialogue.state
+    )
+    .execute(&pool)
+    .await?;
+
+    get_dialogue(Path(chat_id), State(pool)).await
+}
+
+async fn delete_dialogue(
+    Path(chat_id): Path<i64>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<DialogueBody<String>>> {
+    sqlx::query!(
+        r#"
+DELETE FROM dialogues
+WHERE chat_id = ?
+    "#,
+        chat_id
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(Json(DialogueBody {
+        dialogue: chat_id.to_string(),
+    }))
+}
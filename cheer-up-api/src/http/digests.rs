@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Router,
+};
+use cheer_up_core::digest::{
+    Digest, DigestListBody, DigestQuery, DigestSent, DigestSettings, DigestSettingsBody, MuteDigest,
+};
+use cheer_up_core::locale::Locale;
+use sqlx::SqlitePool;
+
+use crate::http::error::{Error, Json, Query, Resource};
+use crate::http::http::Result;
+
+pub fn router(pool: SqlitePool) -> Router<()> {
+    Router::new()
+        .route("/api/digests", get(get_due_digests))
+        .route(
+            "/api/digests/settings/:user_id",
+            get(get_digest_settings).patch(set_digest_muted),
+        )
+        .route("/api/digests/:user_id/sent", post(mark_digest_sent))
+        .with_state(pool)
+}
+
+async fn get_digest_settings(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<DigestSettingsBody<DigestSettings>>> {
+    let settings = sqlx::query_as!(
+        DigestSettings,
+        r#"
+SELECT u.id AS user_id,
+    COALESCE(d.muted, TRUE) AS "muted!: bool",
+    d.last_sent_at
+FROM users u
+LEFT JOIN digest_settings d ON d.user_id = u.id
+WHERE u.id = ?
+    "#,
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::User))?;
+
+    Ok(Json(DigestSettingsBody { settings }))
+}
+
+async fn set_digest_muted(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(body): Json<MuteDigest>,
+) -> Result<Json<DigestSettingsBody<DigestSettings>>> {
+    // INFO: unmuting starts a fresh period, views while muted are never reported
+    let updated = sqlx::query!(
+        r#"
+INSERT INTO digest_settings (user_id, muted)
+SELECT id, ?2 FROM users WHERE id = ?1
+ON CONFLICT (user_id) DO UPDATE
+SET muted = excluded.muted,
+    last_sent_at = CASE
+        WHEN digest_settings.muted AND NOT excluded.muted THEN excluded.last_sent_at
+        ELSE digest_settings.last_sent_at
+    END
+    "#,
+        user_id,
+        body.muted
+    )
+    .execute(&pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(Error::NotFound(Resource::User));
+    }
+
+    get_digest_settings(Path(user_id), State(pool)).await
+}
+
+async fn get_due_digests(
+    Query(query): Query<DigestQuery>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<DigestListBody<Digest>>> {
+    // INFO: the author watching or reacting to their own notes does not count
+    let digests = sqlx::query_as!(
+        Digest,
+        r#"
+WITH clock AS (SELECT strftime('%Y-%m-%d %H:%M:%f', 'now') AS now)
+SELECT u.id AS "user_id!: i64",
+    u.telegram_id AS "telegram_id!: i64",
+    l.language AS "locale!: Locale",
+    d.last_sent_at AS "since!: String",
+    clock.now AS "until!: String",
+    (SELECT COUNT(*) FROM note_views v JOIN notes n ON n.id = v.note_id
+        WHERE n.user_id = u.id AND v.user_id != u.id
+        AND v.viewed_at > d.last_sent_at AND v.viewed_at <= clock.now) AS "views!: i64",
+    (SELECT COUNT(*) FROM reactions r JOIN notes n ON n.id = r.note_id
+        WHERE n.user_id = u.id AND r.user_id != u.id AND r.reaction = 'heart'
+        AND r.created_at > d.last_sent_at AND r.created_at <= clock.now) AS "heart!: i64",
+    (SELECT COUNT(*) FROM reactions r JOIN notes n ON n.id = r.note_id
+        WHERE n.user_id = u.id AND r.user_id != u.id AND r.reaction = 'laugh'
+        AND r.created_at > d.last_sent_at AND r.created_at <= clock.now) AS "laugh!: i64",
+    (SELECT COUNT(*) FROM reactions r JOIN notes n ON n.id = r.note_id
+        WHERE n.user_id = u.id AND r.user_id != u.id AND r.reaction = 'cry'
+        AND r.created_at > d.last_sent_at AND r.created_at <= clock.now) AS "cry!: i64"
+FROM digest_settings d
+JOIN users u ON u.id = d.user_id
+JOIN locales l ON l.id = u.locale
+CROSS JOIN clock
+WHERE NOT d.muted
+    AND d.last_sent_at <= strftime('%Y-%m-%d %H:%M:%f', 'now', '-' || ?1 || ' days')
+    "#,
+        query.period_days
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(DigestListBody { digests }))
+}
+
+async fn mark_digest_sent(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(sent): Json<DigestSent>,
+) -> Result<Json<DigestSettingsBody<DigestSettings>>> {
+    // INFO: `MAX` keeps a late retry from moving the period backwards
+    let updated = sqlx::query!(
+        r#"
+UPDATE digest_settings
+SET last_sent_at = MAX(last_sent_at, ?2)
+WHERE user_id = ?1
+    "#,
+        user_id,
+        sent.until
+    )
+    .execute(&pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(Error::NotFound(Resource::DigestSettings));
+    }
+
+    get_digest_settings(Path(user_id), State(pool)).await
+}
@@ -1,49 +1,216 @@
+use std::fmt;
+
+use async_trait::async_trait;
 use axum::{
-    body::{self, Bytes},
-    http::StatusCode,
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        FromRequest, FromRequestParts, Request,
+    },
+    http::{request::Parts, StatusCode},
     response::{IntoResponse, Response},
 };
+use cheer_up_core::error::{ErrorBody, ErrorCode, ErrorDetails};
+use serde::{de::DeserializeOwned, Serialize};
 
 use sqlx::error::Error as SqlxError;
 
+/// What a [`Error::NotFound`] was looking for, picks its [`ErrorCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Note,
+    User,
+    Group,
+    Subscription,
+    DigestSettings,
+    Dialogue,
+    /// Rows looked up with `fetch_one`, or unknown routes
+    Any,
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resource::Note => write!(f, "note"),
+            Resource::User => write!(f, "user"),
+            Resource::Group => write!(f, "group"),
+            Resource::Subscription => write!(f, "subscription"),
+            Resource::DigestSettings => write!(f, "digest settings"),
+            Resource::Dialogue => write!(f, "dialogue"),
+            Resource::Any => write!(f, "requested entity"),
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("requested entity not found")]
-    NotFound,
+    #[error("{0} not found")]
+    NotFound(Resource),
+    #[error("missing or unknown api key")]
+    Unauthorized,
+    #[error("api key not allowed on this route")]
+    Forbidden,
+    #[error("only the note author or an admin can do this")]
+    NotNoteOwner,
+    #[error("entity already exists")]
+    Conflict,
+    #[error("{0}")]
+    Validation(String),
     #[error("an error occurred with the database")]
-    Sqlx(#[from] SqlxError),
+    Sqlx(SqlxError),
     #[error("internal server error")]
     Anyhow(#[from] anyhow::Error),
 }
 
+// INFO: constraint violations are the client's fault, everything else is ours
+impl From<SqlxError> for Error {
+    fn from(err: SqlxError) -> Self {
+        match err {
+            SqlxError::RowNotFound => Error::NotFound(Resource::Any),
+            SqlxError::Database(ref e) if e.is_unique_violation() => Error::Conflict,
+            SqlxError::Database(ref e) if e.is_foreign_key_violation() => {
+                Error::Validation("referenced entity does not exist".to_string())
+            }
+            SqlxError::Database(ref e) if e.is_check_violation() => {
+                Error::Validation("invalid field value".to_string())
+            }
+            other => Error::Sqlx(other),
+        }
+    }
+}
+
+impl From<JsonRejection> for Error {
+    fn from(rejection: JsonRejection) -> Self {
+        Error::Validation(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for Error {
+    fn from(rejection: QueryRejection) -> Self {
+        Error::Validation(rejection.body_text())
+    }
+}
+
 impl Error {
     pub fn status_code(&self) -> StatusCode {
         match self {
-            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden | Self::NotNoteOwner => StatusCode::FORBIDDEN,
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
             Self::Sqlx(_) | Self::Anyhow(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::NotFound(Resource::Note) => ErrorCode::NoteNotFound,
+            Self::NotFound(Resource::User) => ErrorCode::UserNotFound,
+            Self::NotFound(Resource::Group) => ErrorCode::GroupNotFound,
+            Self::NotFound(Resource::Subscription) => ErrorCode::SubscriptionNotFound,
+            Self::NotFound(Resource::DigestSettings) => ErrorCode::DigestSettingsNotFound,
+            Self::NotFound(Resource::Dialogue) => ErrorCode::DialogueNotFound,
+            Self::NotFound(Resource::Any) => ErrorCode::NotFound,
+            Self::Unauthorized => ErrorCode::Unauthorized,
+            Self::Forbidden => ErrorCode::Forbidden,
+            Self::NotNoteOwner => ErrorCode::NotNoteOwner,
+            Self::Conflict => ErrorCode::AlreadyExists,
+            Self::Validation(_) => ErrorCode::InvalidRequest,
+            Self::Sqlx(_) | Self::Anyhow(_) => ErrorCode::InternalError,
+        }
+    }
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        let body = match self {
+        // INFO: internal details are logged, never sent to clients
+        match self {
             Self::Sqlx(ref e) => {
                 // TODO: USE TRACER
                 log::error!("SQLx error: {:?}", e);
-                format!("SQLx error: {:?}", e)
             }
             Self::Anyhow(ref e) => {
                 // TODO: USE TRACER
                 log::error!("Generic error: {:?}", e);
-                format!("Generic error: {:?}", e)
-            }
-            _ => {
-                //
-                format!("API error")
             }
+            _ => {}
+        };
+
+        let body = ErrorBody {
+            error: ErrorDetails {
+                code: self.code(),
+                message: self.to_string(),
+            },
         };
 
-        (self.status_code(), body).into_response()
+        (self.status_code(), axum::Json(body)).into_response()
+    }
+}
+
+/// `axum::Json` rejecting invalid bodies with an [`Error::Validation`] envelope.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Json(value))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// `axum::extract::Query` rejecting invalid query strings with an [`Error::Validation`] envelope.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(value) =
+            axum::extract::Query::<T>::from_request_parts(parts, state).await?;
+        Ok(Query(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::to_bytes;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn errors_are_json_envelopes() {
+        let response = Error::NotFound(Resource::Note).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: ErrorBody = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.error.code, ErrorCode::NoteNotFound);
+        assert_eq!(body.error.message, "note not found");
+
+        // INFO: database details stay in the logs
+        let response = Error::Sqlx(SqlxError::PoolTimedOut).into_response();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: ErrorBody = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.error.code, ErrorCode::InternalError);
+        assert!(!body.error.message.contains("PoolTimedOut"));
     }
 }
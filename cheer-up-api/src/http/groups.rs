@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Router,
+};
+use cheer_up_core::group::{Group, GroupBody, GroupListBody, JoinGroup, NewGroup};
+use cheer_up_core::locale::Locale;
+use cheer_up_core::user::{Role, User, UserListBody};
+use sqlx::SqlitePool;
+
+use crate::http::error::{Error, Json, Resource};
+use crate::http::http::Result;
+
+pub fn router(pool: SqlitePool) -> Router<()> {
+    Router::new()
+        .route("/api/groups", post(create_group))
+        .route("/api/groups/", post(create_group))
+        .route("/api/groups/join", post(join_group))
+        .route("/api/groups/user/:user_id", get(get_user_groups))
+        .route("/api/groups/user/:user_id/peers", get(get_user_peers))
+        .with_state(pool)
+}
+
+async fn create_group(
+    State(pool): State<SqlitePool>,
+    Json(group): Json<NewGroup>,
+) -> Result<Json<GroupBody<Group>>> {
+    let mut tx = pool.begin().await?;
+
+    // INFO: invite codes are random 10 hex chars, unique index guards collisions
+    let new_group: Group = sqlx::query_as!(
+        Group,
+        r#"
+INSERT INTO groups (name, invite_code, created_by)
+VALUES (?, lower(hex(randomblob(5))), ?);
+
+SELECT id, name, invite_code
+FROM groups
+WHERE id = last_insert_rowid()
+    "#,
+        group.name,
+        group.created_by
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO group_members (group_id, user_id) VALUES (?, ?)",
+        new_group.id,
+        group.created_by
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(GroupBody { group: new_group }))
+}
+
+async fn join_group(
+    State(pool): State<SqlitePool>,
+    Json(join): Json<JoinGroup>,
+) -> Result<Json<GroupBody<Group>>> {
+    let invite_code = join.invite_code.trim().to_lowercase();
+
+    let group: Group = sqlx::query_as!(
+        Group,
+        r#"
+SELECT id, name, invite_code
+FROM groups
+WHERE invite_code = ?
+    "#,
+        invite_code
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::Group))?;
+
+    sqlx::query!(
+        "INSERT OR IGNORE INTO group_members (group_id, user_id) VALUES (?, ?)",
+        group.id,
+        join.user_id
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(Json(GroupBody { group }))
+}
+
+async fn get_user_groups(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<GroupListBody<Group>>> {
+    let groups: Vec<Group> = sqlx::query_as!(
+        Group,
+        r#"
+SELECT g.id, g.name, g.invite_code
+FROM groups g
+INNER JOIN group_members gm ON gm.group_id = g.id
+WHERE gm.user_id = ?
+ORDER BY g.id
+    "#,
+        user_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(GroupListBody { groups }))
+}
+
+async fn get_user_peers(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<UserListBody<User>>> {
+    let users: Vec<User> = sqlx::query_as!(
+        User,
+        r#"
+SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale", u.role AS "role!: Role"
+FROM users AS u
+INNER JOIN locales AS l ON u.locale = l.id
+WHERE u.id IN (SELECT peer_id FROM group_peers WHERE user_id = ?1 AND peer_id != ?1)
+ORDER BY u.username
+    "#,
+        user_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(UserListBody { users }))
+}
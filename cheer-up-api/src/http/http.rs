@@ -1,19 +1,26 @@
-use axum::Router;
+use std::sync::Arc;
+
+use axum::{middleware, Router};
+use cheer_up_core::media::MediaStore;
 use sqlx::SqlitePool;
 use tokio::net::TcpListener;
 
-use crate::http::error::Error;
+use crate::config::Config;
+use crate::http::error::{Error, Resource};
 
-use crate::http::{notes, stats, users};
+use crate::http::auth;
+use crate::http::{
+    dialogues, digests, groups, media, notes, reactions, stats, strategy, subscriptions, users,
+};
 
 use super::locale;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
-pub async fn serve(db_pool: SqlitePool) -> Result<()> {
-    let app = api_router(db_pool);
+pub async fn serve(db_pool: SqlitePool, config: &Config, store: Arc<dyn MediaStore>) -> Result<()> {
+    let app = api_router(db_pool, config, store);
 
-    let listener = TcpListener::bind("0.0.0.0:1989")
+    let listener = TcpListener::bind(config.listen_addr)
         .await
         .expect("error establishing TcpListener");
 
@@ -23,10 +30,26 @@ pub async fn serve(db_pool: SqlitePool) -> Result<()> {
     Ok(())
 }
 
-fn api_router(pool: SqlitePool) -> Router {
+fn api_router(pool: SqlitePool, config: &Config, store: Arc<dyn MediaStore>) -> Router {
     Router::new()
-        .merge(users::router(pool.clone()))
-        .merge(notes::router(pool.clone()))
+        .merge(users::router(pool.clone(), store.clone()))
+        .merge(notes::router(
+            pool.clone(),
+            config.selection_strategy,
+            store.clone(),
+        ))
+        .merge(groups::router(pool.clone()))
+        .merge(reactions::router(pool.clone()))
+        .merge(digests::router(pool.clone()))
+        .merge(subscriptions::router(pool.clone()))
+        .merge(dialogues::router(pool.clone()))
         .merge(locale::router(pool.clone()))
+        .merge(media::router(pool.clone(), store))
+        .merge(strategy::router(pool.clone()))
         .merge(stats::router(pool))
+        .route_layer(middleware::from_fn_with_state(
+            config.api_keys.clone(),
+            auth::require_api_key,
+        ))
+        .fallback(|| async { Error::NotFound(Resource::Any) })
 }
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing::post, Extension, Router};
+use cheer_up_core::media::{GcReport, GcReportBody, MediaStore};
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::gc;
+use crate::http::error::Json;
+use crate::http::http::Result;
+
+pub fn router(pool: SqlitePool, store: Arc<dyn MediaStore>) -> Router<()> {
+    Router::new()
+        .route("/api/media/gc", post(collect_media_garbage))
+        .layer(Extension(store))
+        .with_state(pool)
+}
+
+async fn collect_media_garbage(
+    State(pool): State<SqlitePool>,
+    Extension(store): Extension<Arc<dyn MediaStore>>,
+) -> Result<Json<GcReportBody<GcReport>>> {
+    let gc = gc::collect(&pool, store.as_ref(), Utc::now()).await?;
+
+    Ok(Json(GcReportBody { gc }))
+}
@@ -1,6 +1,14 @@
+pub mod auth;
+pub mod dialogues;
+pub mod digests;
 pub mod error;
+pub mod groups;
 pub mod http;
 pub mod locale;
+pub mod media;
 pub mod notes;
+pub mod reactions;
 pub mod stats;
+pub mod strategy;
+pub mod subscriptions;
 pub mod users;
@@ -1,65 +1,53 @@
+use std::sync::Arc;
+
 use axum::{
     extract::{Path, State},
-    routing::{get, post},
-    Json, Router,
+    routing::{get, post, put},
+    Extension, Router,
+};
+use cheer_up_core::media::MediaStore;
+use cheer_up_core::note::{
+    DeleteNoteQuery, DeleteNotes, NewNote, NewNoteView, Note, NoteBody, NoteListBody,
+    NoteListQuery, NotePage, NoteRecipient, NoteView, NoteViewBody, RandomNoteQuery,
+    SelectionStrategy, MAX_NOTE_CAPTION_LEN, MAX_NOTE_PAGE_SIZE,
 };
-use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use cheer_up_core::user::Role;
+use log::debug;
+use sqlx::{SqliteExecutor, SqlitePool};
+use time::OffsetDateTime;
 
+use crate::gc;
+use crate::http::error::{Error, Json, Query, Resource};
 use crate::http::http::Result;
+use crate::selection::{self, Candidate};
 
 use super::users;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Note {
-    pub id: i64,
-    pub user_id: i64,
-    pub file_name: String,
-}
-
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default)]
-struct DeleteNote {
-    id: Option<i64>,
-    user_id: Option<i64>,
-    file_name: Option<String>,
-}
-
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default)]
-struct UpdateNote {
-    id: Option<i64>,
-    user_id: Option<i64>,
-    file_name: Option<String>,
-}
-
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-pub struct NewNote {
-    user_id: i64,
-    file_name: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct NoteBody<T> {
-    note: T,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct NoteListBody<T> {
-    notes: Vec<T>,
-}
-
-pub fn router(pool: SqlitePool) -> Router<()> {
+pub fn router(
+    pool: SqlitePool,
+    default_strategy: SelectionStrategy,
+    store: Arc<dyn MediaStore>,
+) -> Router<()> {
     Router::new()
-        .route("/api/notes", get(get_notes_list).post(create_note))
+        .route(
+            "/api/notes",
+            get(get_notes_list).post(create_note).delete(delete_notes),
+        )
         // TODO: sure as hell there's a better way to do this without duplication
-        .route("/api/notes/", get(get_notes_list).post(create_note))
+        .route(
+            "/api/notes/",
+            get(get_notes_list).post(create_note).delete(delete_notes),
+        )
         .route("/api/notes/:note_id", get(get_note).delete(delete_note))
         .route(
             "/api/notes/user/:user_id",
             get(get_notes_list_by_user).delete(delete_all_user_notes),
         )
+        .route("/api/notes/:note_id/recipient", put(set_note_recipient))
+        .route("/api/notes/:note_id/views", post(record_note_view))
         .route("/api/notes/random", get(get_random_note))
+        .layer(Extension(default_strategy))
+        .layer(Extension(store))
         .with_state(pool)
 }
 
@@ -70,14 +58,15 @@ async fn get_note(
     let note: Note = sqlx::query_as!(
         Note,
         r#"
-SELECT id, user_id, file_name
+SELECT id, user_id, file_name, file_id, file_unique_id, duration, file_size, media_key, group_id, recipient_id, caption, created_at
 FROM notes
 WHERE id = ?
     "#,
         note_id
     )
-    .fetch_one(&pool)
-    .await?;
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::Note))?;
 
     Ok(Json(NoteBody { note }))
 }
@@ -86,18 +75,35 @@ async fn create_note(
     State(pool): State<SqlitePool>,
     Json(note): Json<NewNote>,
 ) -> Result<Json<NoteBody<Note>>> {
+    if let Some(caption) = &note.caption {
+        if caption.chars().count() > MAX_NOTE_CAPTION_LEN {
+            return Err(Error::Validation(format!(
+                "`caption` must be at most {} characters",
+                MAX_NOTE_CAPTION_LEN
+            )));
+        }
+    }
+
     let new_note = sqlx::query_as!(
         Note,
         r#"
-INSERT INTO notes (user_id, file_name)
-VALUES (?, ?);
+INSERT INTO notes (user_id, file_name, file_id, file_unique_id, duration, file_size, media_key, group_id, recipient_id, caption, created_at)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%Y-%m-%d %H:%M:%f', 'now'));
 
-SELECT id, user_id, file_name
+SELECT id, user_id, file_name, file_id, file_unique_id, duration, file_size, media_key, group_id, recipient_id, caption, created_at
 FROM notes
 WHERE id = last_insert_rowid()
     "#,
         note.user_id,
-        note.file_name
+        note.file_name,
+        note.file_id,
+        note.file_unique_id,
+        note.duration,
+        note.file_size,
+        note.media_key,
+        note.group_id,
+        note.recipient_id,
+        note.caption
     )
     .fetch_one(&pool)
     .await?;
@@ -105,37 +111,319 @@ WHERE id = last_insert_rowid()
     Ok(Json(NoteBody { note: new_note }))
 }
 
-async fn delete_note(
+async fn set_note_recipient(
     Path(note_id): Path<String>,
     State(pool): State<SqlitePool>,
-) -> Result<Json<NoteBody<String>>> {
-    let _note = sqlx::query_as!(
-        Note,
+    Json(recipient): Json<NoteRecipient>,
+) -> Result<Json<NoteBody<Note>>> {
+    // INFO: notes can only be addressed to someone sharing a group with their author
+    let updated = sqlx::query!(
         r#"
-DELETE FROM notes
-WHERE id = ?
+UPDATE notes
+SET recipient_id = ?1
+WHERE id = ?2
+    AND (?1 IS NULL OR ?1 IN (
+        SELECT peer_id FROM group_peers WHERE user_id = notes.user_id AND peer_id != notes.user_id
+    ))
     "#,
+        recipient.recipient_id,
         note_id
     )
     .execute(&pool)
     .await?;
 
+    if updated.rows_affected() == 0 {
+        return Err(Error::NotFound(Resource::Note));
+    }
+
+    get_note(Path(note_id), State(pool)).await
+}
+
+async fn record_note_view(
+    Path(note_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(view): Json<NewNoteView>,
+) -> Result<Json<NoteViewBody<NoteView>>> {
+    let new_view = sqlx::query_as!(
+        NoteView,
+        r#"
+INSERT INTO note_views (note_id, user_id)
+VALUES (?, ?);
+
+SELECT id, note_id, user_id, viewed_at
+FROM note_views
+WHERE id = last_insert_rowid()
+    "#,
+        note_id,
+        view.user_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(NoteViewBody { view: new_view }))
+}
+
+/// Fails with [`Error::NotNoteOwner`] unless `acting_user` is `owner_id` or an admin.
+async fn authorize_note_owner<'e>(
+    executor: impl SqliteExecutor<'e>,
+    owner_id: i64,
+    acting_user: i64,
+) -> Result<()> {
+    let role = sqlx::query_scalar!(
+        r#"SELECT role AS "role!: Role" FROM users WHERE id = ?"#,
+        acting_user
+    )
+    .fetch_optional(executor)
+    .await?
+    .ok_or(Error::NotNoteOwner)?;
+
+    if owner_id != acting_user && role != Role::Admin {
+        debug!(
+            "[AUTHORIZE_NOTE_OWNER] user {} cannot delete notes of user {}",
+            acting_user, owner_id
+        );
+        return Err(Error::NotNoteOwner);
+    }
+
+    Ok(())
+}
+
+async fn delete_note(
+    Path(note_id): Path<String>,
+    Query(query): Query<DeleteNoteQuery>,
+    State(pool): State<SqlitePool>,
+    Extension(store): Extension<Arc<dyn MediaStore>>,
+) -> Result<Json<NoteBody<String>>> {
+    // INFO: checked and deleted in one transaction, the note cannot change owner in between
+    let mut tx = pool.begin().await?;
+
+    let owner_id = sqlx::query_scalar!("SELECT user_id FROM notes WHERE id = ?", note_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(Error::NotFound(Resource::Note))?;
+    authorize_note_owner(&mut *tx, owner_id, query.acting_user).await?;
+
+    let media_keys: Vec<String> = sqlx::query_scalar!(
+        "DELETE FROM notes WHERE id = ? RETURNING media_key",
+        note_id
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    .into_iter()
+    .collect();
+
+    tx.commit().await?;
+    gc::delete_note_media(store.as_ref(), &media_keys).await;
+
     Ok(Json(NoteBody { note: note_id }))
 }
 
-async fn get_notes_list(State(pool): State<SqlitePool>) -> Result<Json<NoteListBody<Note>>> {
-    let notes: Vec<Note> = sqlx::query_as!(
-        Note,
+async fn delete_notes(
+    State(pool): State<SqlitePool>,
+    Extension(store): Extension<Arc<dyn MediaStore>>,
+    Json(delete): Json<DeleteNotes>,
+) -> Result<Json<NoteListBody<i64>>> {
+    if delete.note_ids.is_empty() {
+        return Err(Error::Validation(
+            "`note_ids` must not be empty".to_string(),
+        ));
+    }
+
+    let mut note_ids = delete.note_ids;
+    note_ids.sort_unstable();
+    note_ids.dedup();
+
+    // INFO: one forbidden note cancels the whole batch
+    let mut tx = pool.begin().await?;
+    let mut deleted = Vec::with_capacity(note_ids.len());
+    let mut media_keys = Vec::new();
+
+    for note_id in note_ids {
+        let Some(owner_id) = sqlx::query_scalar!("SELECT user_id FROM notes WHERE id = ?", note_id)
+            .fetch_optional(&mut *tx)
+            .await?
+        else {
+            debug!("[DELETE_NOTES] note {} does not exist, skipping", note_id);
+            continue;
+        };
+        authorize_note_owner(&mut *tx, owner_id, delete.acting_user).await?;
+
+        let media_key = sqlx::query_scalar!(
+            "DELETE FROM notes WHERE id = ? RETURNING media_key",
+            note_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        media_keys.extend(media_key);
+        deleted.push(note_id);
+    }
+
+    tx.commit().await?;
+    gc::delete_note_media(store.as_ref(), &media_keys).await;
+
+    Ok(Json(NoteListBody { notes: deleted }))
+}
+
+// INFO: `created_at` is text, bounds are compared as strings so they must share its format
+fn is_timestamp_prefix(bound: &str) -> bool {
+    const FORMAT: &str = "0000-00-00 00:00:00.000";
+
+    [10, 16, 19, 23].contains(&bound.len())
+        && bound.bytes().zip(FORMAT.bytes()).all(|(c, f)| match f {
+            b'0' => c.is_ascii_digit(),
+            _ => c == f,
+        })
+}
+
+fn validate_note_list_query(query: &NoteListQuery) -> Result<()> {
+    if query.after.is_some() && query.before.is_some() {
+        return Err(Error::Validation(
+            "`after` and `before` cannot be used together".to_string(),
+        ));
+    }
+
+    if let Some(limit) = query.limit {
+        if !(1..=MAX_NOTE_PAGE_SIZE).contains(&limit) {
+            return Err(Error::Validation(format!(
+                "`limit` must be between 1 and {}",
+                MAX_NOTE_PAGE_SIZE
+            )));
+        }
+    }
+
+    for bound in [&query.since, &query.until].into_iter().flatten() {
+        if !is_timestamp_prefix(bound) {
+            return Err(Error::Validation(format!(
+                "`{}` is not a `YYYY-MM-DD[ HH:MM[:SS[.SSS]]]` time",
+                bound
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_notes_list(
+    Query(query): Query<NoteListQuery>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<NotePage>> {
+    validate_note_list_query(&query)?;
+    // INFO: a negative limit is no limit for SQLite
+    let limit = query.limit.unwrap_or(-1);
+
+    // INFO: keyset pagination, `before` walks the ids backwards and the page is
+    //       put back in order at the end. The counts joined on every row tell
+    //       where the page is, an empty page still gets a row with the total
+    let rows = sqlx::query!(
         r#"
-SELECT id, user_id, file_name
-FROM notes
-ORDER BY id
+WITH matching AS (
+    SELECT id, user_id, file_name, file_id, file_unique_id, duration, file_size, media_key, group_id, recipient_id, caption, created_at
+    FROM notes
+    WHERE (?1 IS NULL OR id IN (SELECT note_id FROM note_audience WHERE user_id = ?1))
+        AND (NOT ?2 OR recipient_id = ?1)
+        AND (?3 IS NULL OR user_id = ?3)
+        AND (?4 IS NULL OR recipient_id = ?4)
+        AND (?5 IS NULL OR created_at >= ?5)
+        AND (?6 IS NULL OR created_at < ?6)
+),
+page AS (
+    SELECT *
+    FROM matching
+    WHERE (?7 IS NULL OR id > ?7)
+        AND (?8 IS NULL OR id < ?8)
+    ORDER BY CASE WHEN ?8 IS NULL THEN id ELSE -id END
+    LIMIT ?9
+)
+SELECT
+    counts.total AS "total!: i64",
+    (SELECT COUNT(*) FROM matching WHERE matching.id < page.id) AS "offset!: i64",
+    page.id AS "id?: i64",
+    page.user_id AS "user_id?: i64",
+    page.file_name AS "file_name?: String",
+    page.file_id AS "file_id?: String",
+    page.file_unique_id AS "file_unique_id?: String",
+    page.duration AS "duration?: i64",
+    page.file_size AS "file_size?: i64",
+    page.media_key AS "media_key?: String",
+    page.group_id AS "group_id?: i64",
+    page.recipient_id AS "recipient_id?: i64",
+    page.caption AS "caption?: String",
+    page.created_at AS "created_at?: String"
+FROM (SELECT COUNT(*) AS total FROM matching) AS counts
+LEFT JOIN page ON TRUE
+ORDER BY page.id
     "#,
+        query.for_user,
+        query.personal,
+        query.author,
+        query.recipient,
+        query.since,
+        query.until,
+        query.after,
+        query.before,
+        limit
     )
     .fetch_all(&pool)
     .await?;
 
-    Ok(Json(NoteListBody { notes }))
+    let total = rows.first().map(|row| row.total).unwrap_or_default();
+    let offset = rows.first().map(|row| row.offset);
+
+    let notes: Vec<Note> = rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(Note {
+                id: row.id?,
+                user_id: row.user_id?,
+                file_name: row.file_name?,
+                file_id: row.file_id,
+                file_unique_id: row.file_unique_id,
+                duration: row.duration,
+                file_size: row.file_size,
+                media_key: row.media_key,
+                group_id: row.group_id,
+                recipient_id: row.recipient_id,
+                caption: row.caption,
+                created_at: row.created_at,
+            })
+        })
+        .collect();
+
+    let page = match (notes.first(), notes.last()) {
+        (Some(first), Some(last)) => {
+            let offset = offset.unwrap_or_default();
+            NotePage {
+                total,
+                offset,
+                next_cursor: (offset + (notes.len() as i64) < total).then_some(last.id),
+                prev_cursor: (offset > 0).then_some(first.id),
+                notes,
+            }
+        }
+        // INFO: past either end, the cursors point back to the matching notes
+        _ => match (query.after, query.before) {
+            (Some(after), _) => NotePage {
+                total,
+                offset: total,
+                prev_cursor: (total > 0).then_some(after + 1),
+                ..Default::default()
+            },
+            (_, Some(before)) => NotePage {
+                total,
+                next_cursor: (total > 0).then_some(before - 1),
+                ..Default::default()
+            },
+            _ => NotePage::default(),
+        },
+    };
+    debug!(
+        "[GET_NOTES_LIST] {} of {} notes from offset {}",
+        page.notes.len(),
+        page.total,
+        page.offset
+    );
+
+    Ok(Json(page))
 }
 
 async fn get_notes_list_by_user(
@@ -145,7 +433,7 @@ async fn get_notes_list_by_user(
     let notes: Vec<Note> = sqlx::query_as!(
         Note,
         r#"
-SELECT id, user_id, file_name
+SELECT id, user_id, file_name, file_id, file_unique_id, duration, file_size, media_key, group_id, recipient_id, caption, created_at
 FROM notes
 WHERE user_id = ?
 ORDER BY id
@@ -159,35 +447,238 @@ ORDER BY id
 }
 
 async fn delete_all_user_notes(
-    Path(user_id): Path<String>,
+    Path(user_id): Path<i64>,
+    Query(query): Query<DeleteNoteQuery>,
     State(pool): State<SqlitePool>,
+    Extension(store): Extension<Arc<dyn MediaStore>>,
 ) -> Result<Json<NoteBody<String>>> {
-    let _notes = sqlx::query_as!(
-        Note,
+    authorize_note_owner(&pool, user_id, query.acting_user).await?;
+
+    let media_keys: Vec<String> = sqlx::query_scalar!(
         r#"
 DELETE FROM notes
 WHERE user_id = ?
+RETURNING media_key
     "#,
         user_id,
     )
-    .execute(&pool)
-    .await?;
+    .fetch_all(&pool)
+    .await?
+    .into_iter()
+    .flatten()
+    .collect();
+    gc::delete_note_media(store.as_ref(), &media_keys).await;
 
-    Ok(Json(NoteBody { note: user_id }))
+    Ok(Json(NoteBody {
+        note: user_id.to_string(),
+    }))
 }
 
-async fn get_random_note(State(pool): State<SqlitePool>) -> Result<Json<NoteBody<Note>>> {
-    let note: Note = sqlx::query_as!(
-        Note,
+async fn get_random_note(
+    Query(query): Query<RandomNoteQuery>,
+    State(pool): State<SqlitePool>,
+    Extension(default_strategy): Extension<SelectionStrategy>,
+) -> Result<Json<NoteBody<Note>>> {
+    // INFO: query param > user preference > deployment default
+    let user_strategy = match (query.strategy, query.for_user) {
+        (None, Some(user_id)) => sqlx::query_scalar!(
+            r#"SELECT selection_strategy AS "selection_strategy: SelectionStrategy" FROM users WHERE id = ?"#,
+            user_id
+        )
+        .fetch_optional(&pool)
+        .await?
+        .flatten(),
+        _ => None,
+    };
+    let strategy = query.strategy.or(user_strategy).unwrap_or(default_strategy);
+    debug!("[GET_RANDOM_NOTE] strategy is: {:?}", strategy);
+
+    // INFO: every note the requester can receive is weighed in memory, a friends
+    //      circle uploads hundreds of notes at most
+    let rows = sqlx::query!(
         r#"
-SELECT id, user_id, file_name
-FROM notes
-ORDER BY RANDOM()
-LIMIT 1
+SELECT n.id, n.user_id, n.file_name, n.file_id, n.file_unique_id, n.duration, n.file_size,
+    n.media_key, n.group_id, n.recipient_id, n.caption, n.created_at,
+    julianday('now') - julianday(n.created_at) AS "age_days: f64",
+    CAST(strftime('%H', n.created_at) AS INTEGER) AS "upload_hour: i64",
+    (SELECT COUNT(*) FROM favorites f WHERE f.note_id = n.id) AS "favorites!: i64",
+    v.last_viewed_at AS "last_viewed_at: String"
+FROM notes n
+LEFT JOIN (
+    SELECT note_id, MAX(viewed_at) AS last_viewed_at
+    FROM note_views
+    WHERE user_id = ?1
+    GROUP BY note_id
+) v ON v.note_id = n.id
+WHERE (?1 IS NULL OR n.id IN (SELECT note_id FROM note_audience WHERE user_id = ?1))
+    AND (NOT ?2 OR n.recipient_id = ?1)
+ORDER BY n.id
     "#,
+        query.for_user,
+        query.personal
     )
-    .fetch_one(&pool)
+    .fetch_all(&pool)
     .await?;
 
+    let candidates: Vec<Candidate> = rows
+        .into_iter()
+        .map(|row| Candidate {
+            note: Note {
+                id: row.id,
+                user_id: row.user_id,
+                file_name: row.file_name,
+                file_id: row.file_id,
+                file_unique_id: row.file_unique_id,
+                duration: row.duration,
+                file_size: row.file_size,
+                media_key: row.media_key,
+                group_id: row.group_id,
+                recipient_id: row.recipient_id,
+                caption: row.caption,
+                created_at: row.created_at,
+            },
+            age_days: row.age_days,
+            upload_hour: row.upload_hour,
+            favorites: row.favorites,
+            last_viewed_at: row.last_viewed_at,
+        })
+        .collect();
+
+    let now_hour = OffsetDateTime::now_utc().hour() as i64;
+    let mut rng = selection::rng(query.seed);
+
+    let index = selection::pick(strategy, &candidates, now_hour, &mut rng)
+        .ok_or(Error::NotFound(Resource::Note))?;
+    let note = candidates[index].note.clone();
+
     Ok(Json(NoteBody { note }))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use cheer_up_core::error::{ErrorBody, ErrorCode};
+    use cheer_up_core::media::LocalMediaStore;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const AUTHOR: i64 = 1;
+    const ADMIN: i64 = 2;
+    const FRIEND: i64 = 3;
+
+    async fn pool() -> SqlitePool {
+        // INFO: a single connection, every connection to `:memory:` is a new database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        // INFO: locales are inserted by the migrations
+        let locale = sqlx::query_scalar!("SELECT id FROM locales WHERE language = 'en'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        sqlx::query!(
+            r#"
+INSERT INTO users (id, telegram_id, username, first_name, locale, role) VALUES
+    (1, 100, 'author', 'Author', ?1, 'user'),
+    (2, 200, 'admin', 'Admin', ?1, 'admin'),
+    (3, 300, 'friend', 'Friend', ?1, 'user')
+    "#,
+            locale
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    async fn create_author_note(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar!(
+            "INSERT INTO notes (user_id, file_name) VALUES (?, 'note.mpeg') RETURNING id",
+            AUTHOR
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn delete_as(pool: &SqlitePool, note_id: i64, acting_user: i64) -> StatusCode {
+        let store = Arc::new(LocalMediaStore::new(
+            std::env::temp_dir().join("cheer-up-notes-tests"),
+        ));
+        let app = router(pool.clone(), SelectionStrategy::default(), store);
+
+        let request = Request::delete(format!(
+            "/api/notes/{}?acting_user={}",
+            note_id, acting_user
+        ))
+        .body(Body::empty())
+        .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+
+        if status == StatusCode::FORBIDDEN {
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body: ErrorBody = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body.error.code, ErrorCode::NotNoteOwner);
+        }
+
+        status
+    }
+
+    async fn note_exists(pool: &SqlitePool, note_id: i64) -> bool {
+        sqlx::query_scalar!("SELECT id FROM notes WHERE id = ?", note_id)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    #[tokio::test]
+    async fn authors_can_delete_their_notes() {
+        let pool = pool().await;
+        let note_id = create_author_note(&pool).await;
+
+        assert_eq!(delete_as(&pool, note_id, AUTHOR).await, StatusCode::OK);
+        assert!(!note_exists(&pool, note_id).await);
+    }
+
+    #[tokio::test]
+    async fn admins_can_delete_notes_of_others() {
+        let pool = pool().await;
+        let note_id = create_author_note(&pool).await;
+
+        assert_eq!(delete_as(&pool, note_id, ADMIN).await, StatusCode::OK);
+        assert!(!note_exists(&pool, note_id).await);
+    }
+
+    #[tokio::test]
+    async fn other_users_cannot_delete_notes() {
+        let pool = pool().await;
+        let note_id = create_author_note(&pool).await;
+
+        assert_eq!(
+            delete_as(&pool, note_id, FRIEND).await,
+            StatusCode::FORBIDDEN
+        );
+        // INFO: unknown users are not admins either
+        assert_eq!(delete_as(&pool, note_id, 42).await, StatusCode::FORBIDDEN);
+        assert!(note_exists(&pool, note_id).await);
+
+        assert!(matches!(
+            authorize_note_owner(&pool, AUTHOR, FRIEND).await,
+            Err(Error::NotNoteOwner)
+        ));
+        assert!(authorize_note_owner(&pool, AUTHOR, AUTHOR).await.is_ok());
+        assert!(authorize_note_owner(&pool, AUTHOR, ADMIN).await.is_ok());
+    }
+}
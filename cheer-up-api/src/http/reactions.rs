@@ -0,0 +1,162 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Router,
+};
+use cheer_up_core::note::{Note, NoteBody, NoteListBody};
+use cheer_up_core::reaction::{
+    NewFavorite, NewReaction, NoteReactions, ReactionListBody, ReactionsBody,
+};
+use sqlx::SqlitePool;
+
+use crate::http::error::{Error, Json, Resource};
+use crate::http::http::Result;
+
+pub fn router(pool: SqlitePool) -> Router<()> {
+    Router::new()
+        .route(
+            "/api/notes/:note_id/reactions",
+            get(get_note_reactions).post(react_to_note),
+        )
+        .route(
+            "/api/notes/user/:user_id/reactions",
+            get(get_user_notes_reactions),
+        )
+        .route(
+            "/api/users/:user_id/favorites",
+            get(get_user_favorites).post(add_user_favorite),
+        )
+        .with_state(pool)
+}
+
+async fn get_note_reactions(
+    Path(note_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<ReactionsBody<NoteReactions>>> {
+    let reactions: NoteReactions = sqlx::query_as!(
+        NoteReactions,
+        r#"
+SELECT n.id AS note_id,
+    (SELECT COUNT(*) FROM reactions r WHERE r.note_id = n.id AND r.reaction = 'heart') AS "heart!: i64",
+    (SELECT COUNT(*) FROM reactions r WHERE r.note_id = n.id AND r.reaction = 'laugh') AS "laugh!: i64",
+    (SELECT COUNT(*) FROM reactions r WHERE r.note_id = n.id AND r.reaction = 'cry') AS "cry!: i64",
+    (SELECT COUNT(*) FROM favorites f WHERE f.note_id = n.id) AS "favorites!: i64"
+FROM notes n
+WHERE n.id = ?
+    "#,
+        note_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::Note))?;
+
+    Ok(Json(ReactionsBody { reactions }))
+}
+
+async fn react_to_note(
+    Path(note_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(reaction): Json<NewReaction>,
+) -> Result<Json<ReactionsBody<NoteReactions>>> {
+    // INFO: users can only react to notes they could receive
+    let reacted = sqlx::query!(
+        r#"
+INSERT INTO reactions (user_id, note_id, reaction)
+SELECT ?1, ?2, ?3
+WHERE ?2 IN (SELECT note_id FROM note_audience WHERE user_id = ?1)
+ON CONFLICT (user_id, note_id) DO UPDATE
+SET reaction = excluded.reaction, created_at = excluded.created_at
+    "#,
+        reaction.user_id,
+        note_id,
+        reaction.reaction
+    )
+    .execute(&pool)
+    .await?;
+
+    if reacted.rows_affected() == 0 {
+        return Err(Error::NotFound(Resource::Note));
+    }
+
+    get_note_reactions(Path(note_id), State(pool)).await
+}
+
+async fn get_user_notes_reactions(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<ReactionListBody<NoteReactions>>> {
+    let reactions: Vec<NoteReactions> = sqlx::query_as!(
+        NoteReactions,
+        r#"
+SELECT n.id AS note_id,
+    (SELECT COUNT(*) FROM reactions r WHERE r.note_id = n.id AND r.reaction = 'heart') AS "heart!: i64",
+    (SELECT COUNT(*) FROM reactions r WHERE r.note_id = n.id AND r.reaction = 'laugh') AS "laugh!: i64",
+    (SELECT COUNT(*) FROM reactions r WHERE r.note_id = n.id AND r.reaction = 'cry') AS "cry!: i64",
+    (SELECT COUNT(*) FROM favorites f WHERE f.note_id = n.id) AS "favorites!: i64"
+FROM notes n
+WHERE n.user_id = ?
+ORDER BY n.id
+    "#,
+        user_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ReactionListBody { reactions }))
+}
+
+async fn get_user_favorites(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<NoteListBody<Note>>> {
+    let notes: Vec<Note> = sqlx::query_as!(
+        Note,
+        r#"
+SELECT n.id, n.user_id, n.file_name, n.file_id, n.file_unique_id, n.duration, n.file_size, n.media_key, n.group_id, n.recipient_id, n.caption, n.created_at
+FROM notes n
+INNER JOIN favorites f ON f.note_id = n.id
+WHERE f.user_id = ?
+ORDER BY f.created_at
+    "#,
+        user_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(NoteListBody { notes }))
+}
+
+async fn add_user_favorite(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(favorite): Json<NewFavorite>,
+) -> Result<Json<NoteBody<Note>>> {
+    // INFO: saving twice is fine, saving a note the user cannot receive is not
+    let _ = sqlx::query!(
+        r#"
+INSERT OR IGNORE INTO favorites (user_id, note_id)
+SELECT ?1, ?2
+WHERE ?2 IN (SELECT note_id FROM note_audience WHERE user_id = ?1)
+    "#,
+        user_id,
+        favorite.note_id
+    )
+    .execute(&pool)
+    .await?;
+
+    let note: Note = sqlx::query_as!(
+        Note,
+        r#"
+SELECT n.id, n.user_id, n.file_name, n.file_id, n.file_unique_id, n.duration, n.file_size, n.media_key, n.group_id, n.recipient_id, n.caption, n.created_at
+FROM notes n
+INNER JOIN favorites f ON f.note_id = n.id
+WHERE f.user_id = ? AND n.id = ?
+    "#,
+        user_id,
+        favorite.note_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(NoteBody { note }))
+}
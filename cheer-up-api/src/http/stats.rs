@@ -1,31 +1,14 @@
 use axum::{
     extract::{Path, State},
     routing::{get, post},
-    Extension, Json, Router,
+    Extension, Router,
 };
-use serde::{Deserialize, Serialize};
+use cheer_up_core::stats::{Stats, StatsBody, StatsQuery, UserStats};
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 
-use crate::http::error::Error;
+use crate::http::error::{Json, Query};
 use crate::http::http::Result;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Stats {
-    total_videonotes: i32,
-    users: Vec<UserStats>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UserStats {
-    username: String,
-    videonotes: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct StatsBody<T> {
-    stats: T,
-}
-
 pub fn router(pool: SqlitePool) -> Router<()> {
     Router::new()
         .route("/api/stats", get(get_stats))
@@ -34,24 +17,33 @@ pub fn router(pool: SqlitePool) -> Router<()> {
         .with_state(pool)
 }
 
-async fn get_stats(State(pool): State<SqlitePool>) -> Result<Json<StatsBody<Stats>>> {
+async fn get_stats(
+    Query(query): Query<StatsQuery>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<StatsBody<Stats>>> {
+    // INFO: with `for_user` only group peers and notes shared with the user are counted
     let users: Vec<UserStats> = sqlx::query_as!(
         UserStats,
         r#"
-SELECT u.username, COUNT(n.id) AS videonotes
+SELECT u.username, COUNT(n.id) AS "videonotes!: i64"
 FROM users u
 LEFT JOIN notes n ON u.id = n.user_id
+    AND (?1 IS NULL OR n.id IN (SELECT note_id FROM note_audience WHERE user_id = ?1))
+WHERE ?1 IS NULL OR u.id IN (SELECT peer_id FROM group_peers WHERE user_id = ?1)
 GROUP BY u.id
     "#,
+        query.for_user
     )
     .fetch_all(&pool)
     .await?;
 
     let total_videonotes: i32 = sqlx::query_scalar!(
         r#"
-SELECT COUNT(n.id) AS total_videonotes
+SELECT COUNT(n.id) AS "total_videonotes!: i32"
 FROM notes n
+WHERE ?1 IS NULL OR n.id IN (SELECT note_id FROM note_audience WHERE user_id = ?1)
     "#,
+        query.for_user
     )
     .fetch_one(&pool)
     .await?;
@@ -71,7 +63,7 @@ async fn get_user_stats(
     let stats: UserStats = sqlx::query_as!(
         UserStats,
         r#"
-SELECT u.username, COUNT(n.id) AS videonotes
+SELECT u.username, COUNT(n.id) AS "videonotes!: i64"
 FROM users u
 LEFT JOIN notes n ON u.id = n.user_id
 WHERE u.id = ?
@@ -0,0 +1,62 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Router,
+};
+use cheer_up_core::note::{SelectionStrategy, StrategyBody};
+use sqlx::SqlitePool;
+
+use crate::http::error::{Error, Json, Resource};
+use crate::http::http::Result;
+
+pub fn router(pool: SqlitePool) -> Router<()> {
+    Router::new()
+        .route(
+            "/api/strategy/:user_id",
+            get(get_user_strategy).patch(set_user_strategy),
+        )
+        .with_state(pool)
+}
+
+async fn get_user_strategy(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<StrategyBody<Option<SelectionStrategy>>>> {
+    let strategy = sqlx::query_scalar!(
+        r#"
+SELECT selection_strategy AS "selection_strategy: SelectionStrategy"
+FROM users
+WHERE id = ?
+    "#,
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::User))?;
+
+    Ok(Json(StrategyBody { strategy }))
+}
+
+async fn set_user_strategy(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(body): Json<StrategyBody<Option<SelectionStrategy>>>,
+) -> Result<Json<StrategyBody<Option<SelectionStrategy>>>> {
+    let updated = sqlx::query!(
+        r#"
+UPDATE users
+SET selection_strategy = ?
+WHERE id = ?
+    "#,
+        body.strategy,
+        user_id,
+    )
+    .execute(&pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(Error::NotFound(Resource::User));
+    }
+
+    Ok(Json(body))
+}
@@ -0,0 +1,149 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Router,
+};
+use cheer_up_core::subscription::{
+    Frequency, NewSubscription, Subscription, SubscriptionBody, SubscriptionDelivered,
+    SubscriptionListBody,
+};
+use sqlx::SqlitePool;
+
+use crate::http::error::{Error, Json, Resource};
+use crate::http::http::Result;
+
+pub fn router(pool: SqlitePool) -> Router<()> {
+    Router::new()
+        .route("/api/subscriptions/due", get(get_due_subscriptions))
+        .route(
+            "/api/subscriptions/:user_id",
+            get(get_subscription)
+                .put(set_subscription)
+                .delete(delete_subscription),
+        )
+        .route(
+            "/api/subscriptions/:user_id/delivered",
+            post(mark_subscription_delivered),
+        )
+        .with_state(pool)
+}
+
+async fn get_subscription(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<SubscriptionBody<Subscription>>> {
+    let subscription = sqlx::query_as!(
+        Subscription,
+        r#"
+SELECT s.user_id AS "user_id!: i64", u.telegram_id, s.frequency AS "frequency: Frequency", s.local_time,
+    s.timezone, s.weekday, s.next_delivery_at, s.last_delivered_at
+FROM subscriptions s
+JOIN users u ON u.id = s.user_id
+WHERE s.user_id = ?
+    "#,
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::Subscription))?;
+
+    Ok(Json(SubscriptionBody { subscription }))
+}
+
+async fn set_subscription(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(subscription): Json<NewSubscription>,
+) -> Result<Json<SubscriptionBody<Subscription>>> {
+    let updated = sqlx::query!(
+        r#"
+INSERT INTO subscriptions (user_id, frequency, local_time, timezone, weekday, next_delivery_at)
+SELECT id, ?2, ?3, ?4, ?5, ?6 FROM users WHERE id = ?1
+ON CONFLICT (user_id) DO UPDATE
+SET frequency = excluded.frequency,
+    local_time = excluded.local_time,
+    timezone = excluded.timezone,
+    weekday = excluded.weekday,
+    next_delivery_at = excluded.next_delivery_at
+    "#,
+        user_id,
+        subscription.frequency,
+        subscription.local_time,
+        subscription.timezone,
+        subscription.weekday,
+        subscription.next_delivery_at
+    )
+    .execute(&pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(Error::NotFound(Resource::User));
+    }
+
+    get_subscription(Path(user_id), State(pool)).await
+}
+
+async fn delete_subscription(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<SubscriptionBody<String>>> {
+    sqlx::query!(
+        r#"
+DELETE FROM subscriptions
+WHERE user_id = ?
+    "#,
+        user_id
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(Json(SubscriptionBody {
+        subscription: user_id,
+    }))
+}
+
+async fn get_due_subscriptions(
+    State(pool): State<SqlitePool>,
+) -> Result<Json<SubscriptionListBody<Subscription>>> {
+    // INFO: deliveries missed while the bot was down are due as well
+    let subscriptions = sqlx::query_as!(
+        Subscription,
+        r#"
+SELECT s.user_id AS "user_id!: i64", u.telegram_id, s.frequency AS "frequency: Frequency", s.local_time,
+    s.timezone, s.weekday, s.next_delivery_at, s.last_delivered_at
+FROM subscriptions s
+JOIN users u ON u.id = s.user_id
+WHERE s.next_delivery_at <= strftime('%Y-%m-%d %H:%M:%S', 'now')
+ORDER BY s.next_delivery_at
+    "#
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(SubscriptionListBody { subscriptions }))
+}
+
+async fn mark_subscription_delivered(
+    Path(user_id): Path<String>,
+    State(pool): State<SqlitePool>,
+    Json(delivered): Json<SubscriptionDelivered>,
+) -> Result<Json<SubscriptionBody<Subscription>>> {
+    let updated = sqlx::query!(
+        r#"
+UPDATE subscriptions
+SET next_delivery_at = ?2,
+    last_delivered_at = strftime('%Y-%m-%d %H:%M:%S', 'now')
+WHERE user_id = ?1
+    "#,
+        user_id,
+        delivered.next_delivery_at
+    )
+    .execute(&pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(Error::NotFound(Resource::Subscription));
+    }
+
+    get_subscription(Path(user_id), State(pool)).await
+}
@@ -1,57 +1,21 @@
+use std::sync::Arc;
+
 use axum::{
     extract::{Path, State},
     routing::{get, post},
-    Extension, Json, Router,
+    Extension, Router,
 };
+use cheer_up_core::locale::Locale;
+use cheer_up_core::media::MediaStore;
+use cheer_up_core::user::{NewUser, Role, User, UserBody, UserListBody};
 use log::debug;
-use serde::{Deserialize, Serialize};
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 
-use crate::http::error::Error;
+use crate::gc;
+use crate::http::error::{Error, Json, Resource};
 use crate::http::http::Result;
-use crate::http::locale::Locale;
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, sqlx::Type)]
-pub struct User {
-    id: i64,
-    telegram_id: i64,
-    username: String,
-    first_name: String,
-    last_name: Option<String>,
-    locale: Locale,
-}
-
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default)]
-struct UpdateUser {
-    id: Option<i64>,
-    telegram_id: Option<i64>,
-    username: Option<String>,
-    first_name: Option<String>,
-    last_name: Option<String>,
-    locale: Option<i64>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, sqlx::Type)]
-pub struct NewUser {
-    telegram_id: i64,
-    username: String,
-    first_name: String,
-    last_name: Option<String>,
-    locale: Locale,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct UserBody<T> {
-    user: T,
-}
 
-#[derive(Debug, Serialize, Deserialize)]
-struct UserListBody<T> {
-    users: Vec<T>,
-}
-
-pub fn router(pool: SqlitePool) -> Router<()> {
+pub fn router(pool: SqlitePool, store: Arc<dyn MediaStore>) -> Router<()> {
     Router::new()
         .route("/api/users", get(get_users_list).post(create_user))
         .route("/api/users/", get(get_users_list).post(create_user))
@@ -60,6 +24,11 @@ pub fn router(pool: SqlitePool) -> Router<()> {
             "/api/users/name/:username",
             get(get_user_by_telegram_username),
         )
+        .route(
+            "/api/users/telegram/:telegram_id",
+            get(get_user_by_telegram_id).put(upsert_user),
+        )
+        .layer(Extension(store))
         .with_state(pool)
 }
 
@@ -67,7 +36,7 @@ async fn get_users_list(State(pool): State<SqlitePool>) -> Result<Json<UserListB
     let users: Vec<User> = sqlx::query_as!(
         User,
         r#"
-SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale"
+SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale", u.role AS "role!: Role"
 FROM users AS u
 INNER JOIN locales AS l ON u.locale = l.id
 ORDER BY u.id
@@ -86,15 +55,16 @@ async fn get_user(
     let user: User = sqlx::query_as!(
         User,
         r#"
-SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale"
+SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale", u.role AS "role!: Role"
 FROM users AS u
 INNER JOIN locales AS l ON u.locale = l.id
 WHERE u.id = ?
     "#,
         user_id
     )
-    .fetch_one(&pool)
-    .await?;
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::User))?;
 
     Ok(Json(UserBody { user }))
 }
@@ -106,15 +76,16 @@ async fn get_user_by_telegram_username(
     let user: User = sqlx::query_as!(
         User,
         r#"
-SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale"
+SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale", u.role AS "role!: Role"
 FROM users AS u
 INNER JOIN locales AS l ON u.locale = l.id
 WHERE u.username = ?
     "#,
         username
     )
-    .fetch_one(&pool)
-    .await?;
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::User))?;
 
     Ok(Json(UserBody { user }))
 }
@@ -130,7 +101,7 @@ async fn create_user(
         INSERT INTO users (telegram_id, username, first_name, last_name, locale)
         VALUES (?, ?, ?, ?, (SELECT id FROM locales WHERE language = ?));
 
-        SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale"
+        SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale", u.role AS "role!: Role"
         FROM users AS u
         INNER JOIN locales AS l ON u.locale = l.id
         WHERE u.id = last_insert_rowid()
@@ -147,10 +118,96 @@ async fn create_user(
     Ok(Json(UserBody { user }))
 }
 
+async fn get_user_by_telegram_id(
+    Path(telegram_id): Path<i64>,
+    State(pool): State<SqlitePool>,
+) -> Result<Json<UserBody<User>>> {
+    let user: User = sqlx::query_as!(
+        User,
+        r#"
+SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, l.language AS "locale: Locale", u.role AS "role!: Role"
+FROM users AS u
+INNER JOIN locales AS l ON u.locale = l.id
+WHERE u.telegram_id = ?
+    "#,
+        telegram_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(Error::NotFound(Resource::User))?;
+
+    Ok(Json(UserBody { user }))
+}
+
+/// Registers the telegram account, or refreshes its names if already registered.
+/// `locale` is only used on registration, users pick it later on.
+async fn upsert_user(
+    Path(telegram_id): Path<i64>,
+    State(pool): State<SqlitePool>,
+    Json(user): Json<NewUser>,
+) -> Result<Json<UserBody<User>>> {
+    if user.telegram_id != telegram_id {
+        return Err(Error::Validation(
+            "telegram_id does not match the path".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    // INFO: usernames are unique but telegram lets users give theirs up, whoever
+    //       held it before falls back to their telegram id until they show up again
+    sqlx::query!(
+        r#"
+UPDATE users
+SET username = CAST(telegram_id AS TEXT)
+WHERE username = ?1 AND telegram_id != ?2
+    "#,
+        user.username,
+        telegram_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+INSERT INTO users (telegram_id, username, first_name, last_name, locale)
+VALUES (?1, ?2, ?3, ?4, (SELECT id FROM locales WHERE language = ?5))
+ON CONFLICT (telegram_id) DO UPDATE
+SET username = excluded.username, first_name = excluded.first_name, last_name = excluded.last_name
+    "#,
+        telegram_id,
+        user.username,
+        user.first_name,
+        user.last_name,
+        user.locale
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    get_user_by_telegram_id(Path(telegram_id), State(pool)).await
+}
+
 async fn delete_user(
     Path(user_id): Path<String>,
     State(pool): State<SqlitePool>,
+    Extension(store): Extension<Arc<dyn MediaStore>>,
 ) -> Result<Json<UserBody<String>>> {
+    let mut tx = pool.begin().await?;
+
+    // INFO: notes written by the user or addressed to them go with the user
+    let media_keys: Vec<String> = sqlx::query_scalar!(
+        r#"
+SELECT media_key AS "media_key!"
+FROM notes
+WHERE (user_id = ?1 OR recipient_id = ?1) AND media_key IS NOT NULL
+    "#,
+        user_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
     let _user = sqlx::query_as!(
         User,
         r#"
@@ -159,8 +216,11 @@ WHERE id = ?
     "#,
         user_id
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await?;
 
+    tx.commit().await?;
+    gc::delete_note_media(store.as_ref(), &media_keys).await;
+
     Ok(Json(UserBody { user: user_id }))
 }
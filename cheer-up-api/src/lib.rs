@@ -1,3 +1,7 @@
 pub mod http;
 
 pub mod config;
+pub mod gc;
+pub mod media_keys;
+pub mod seed;
+pub mod selection;
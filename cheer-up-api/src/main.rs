@@ -1,21 +1,40 @@
 extern crate pretty_env_logger;
 
 use anyhow::Ok;
+use cheer_up_core::locale::Locale;
+use clap::Parser;
 use sqlx::{pool, sqlite::SqlitePoolOptions};
 // use time::Duration;
 use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 // mod http;
+use cheer_up_api::config::{Config, ConfigArgs};
+use cheer_up_api::gc;
 use cheer_up_api::http::error;
 use cheer_up_api::http::http;
+use cheer_up_api::media_keys;
+use cheer_up_api::seed;
 
 #[macro_use]
 extern crate log;
 
+#[derive(Debug, Parser)]
+#[command(about = "CheerUp REST api server")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     pretty_env_logger::init();
+    dotenvy::dotenv().ok();
+
+    // INFO: validate configuration once, before opening database or sockets
+    let cli = Cli::parse();
+    let config = Config::load(cli.config)?;
+    debug!("config is: {:?}", config);
 
     // tracing_subscriber::registry()
     //     .with(EnvFilter::new(std::env::var("RUST_LOG").unwrap_or_else(
@@ -29,12 +48,39 @@ async fn main() -> Result<(), anyhow::Error> {
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
         .acquire_timeout(Duration::from_secs(3))
-        .connect("sqlite://../_common_data/data/database.db?mode=rwc")
+        .connect(&config.database_url)
         .await
         .expect("cannot connect to database");
 
     sqlx::migrate!().run(&pool).await?;
 
-    let app = http::serve(pool).await?;
+    // INFO: keep `locales` table in sync with languages supported by cheer-up-core
+    for locale in Locale::ALL {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO locales (language) VALUES (?)",
+            locale
+        )
+        .execute(&pool)
+        .await?;
+    }
+
+    // INFO: one store shared by the seed, the garbage collector and the http routes
+    let store = config.media.build();
+
+    // INFO: notes from before `media_key` may point to the wrong folder of their author
+    if let Err(e) = media_keys::reconcile(&pool, store.as_ref()).await {
+        warn!("cannot reconcile note media keys: {}", e);
+    }
+
+    // INFO: development fixtures, `Config::load` refuses `--seed` in production
+    if let Some(fixtures) = &config.seed {
+        info!("seeding database from {:?}", fixtures);
+        seed::run(&pool, store.as_ref(), fixtures).await?;
+    }
+
+    // INFO: files of deleted notes are collected in the background, see `gc`
+    tokio::spawn(gc::run(pool.clone(), store.clone()));
+
+    http::serve(pool, &config, store).await?;
     Ok(())
 }
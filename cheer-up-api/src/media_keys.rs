@@ -0,0 +1,197 @@
+use std::collections::{BTreeMap, HashSet};
+
+use cheer_up_core::media::{parse_media_key, MediaEntry, MediaStore};
+use log::info;
+use sqlx::SqlitePool;
+
+/// Points the notes whose `media_key` is missing from `store` to the file the bots
+/// actually stored for them. Files were stored under the telegram username of the
+/// author at upload time, which the `users` table may not know, so any
+/// `<telegram_id>_*/<file_name>` file of the author is accepted.
+/// Returns the ids of the notes updated.
+pub async fn reconcile(pool: &SqlitePool, store: &dyn MediaStore) -> anyhow::Result<Vec<i64>> {
+    let notes = stored_notes(pool).await?;
+    let entries = store.list("").await?;
+    let fixes = resolve(&entries, &notes);
+
+    for (id, media_key) in &fixes {
+        sqlx::query!("UPDATE notes SET media_key = ? WHERE id = ?", media_key, id)
+            .execute(pool)
+            .await?;
+        info!("[RECONCILE] note {} is stored as {}", id, media_key);
+    }
+
+    Ok(fixes.into_iter().map(|(id, _)| id).collect())
+}
+
+/// A note with a file in the media store, along with what its key is made of.
+#[derive(Debug, Clone)]
+pub(crate) struct StoredNote {
+    pub(crate) id: i64,
+    pub(crate) telegram_id: i64,
+    pub(crate) file_name: String,
+    pub(crate) media_key: String,
+}
+
+pub(crate) async fn stored_notes(pool: &SqlitePool) -> sqlx::Result<Vec<StoredNote>> {
+    let notes = sqlx::query!(
+        r#"
+SELECT notes.id, notes.file_name, notes.media_key AS "media_key!", users.telegram_id
+FROM notes
+INNER JOIN users ON users.id = notes.user_id
+WHERE notes.media_key IS NOT NULL
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(notes
+        .into_iter()
+        .map(|n| StoredNote {
+            id: n.id,
+            telegram_id: n.telegram_id,
+            file_name: n.file_name,
+            media_key: n.media_key,
+        })
+        .collect())
+}
+
+/// New media key of each note whose key is not in `entries` but whose file is,
+/// under another folder of the same author.
+fn resolve(entries: &[MediaEntry], notes: &[StoredNote]) -> Vec<(i64, String)> {
+    let stored: HashSet<&str> = entries.iter().map(|entry| entry.key.as_str()).collect();
+
+    // INFO: sorted so that the same key wins on every run
+    let mut by_file: BTreeMap<(i64, &str), &str> = BTreeMap::new();
+    let mut keys: Vec<&str> = stored.iter().copied().collect();
+    keys.sort_unstable();
+    for key in keys {
+        if let Some(file) = parse_media_key(key) {
+            by_file.entry(file).or_insert(key);
+        }
+    }
+
+    notes
+        .iter()
+        .filter(|note| !stored.contains(note.media_key.as_str()))
+        .filter_map(|note| {
+            by_file
+                .get(&(note.telegram_id, note.file_name.as_str()))
+                .map(|key| (note.id, key.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use sqlx::{migrate::Migrator, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    fn entry(key: &str) -> MediaEntry {
+        MediaEntry {
+            key: key.to_string(),
+            size: 1,
+            last_modified: None,
+        }
+    }
+
+    fn note(id: i64, telegram_id: i64, file_name: &str, media_key: &str) -> StoredNote {
+        StoredNote {
+            id,
+            telegram_id,
+            file_name: file_name.to_string(),
+            media_key: media_key.to_string(),
+        }
+    }
+
+    #[test]
+    fn finds_files_stored_under_another_username() {
+        let entries = vec![
+            entry("100_ana/kept.mpeg"),
+            entry("200_none/nameless.mpeg"),
+            entry("300_old_name/renamed.mpeg"),
+            entry("400_bob/other_author.mpeg"),
+        ];
+        let notes = vec![
+            note(1, 100, "kept.mpeg", "100_ana/kept.mpeg"),
+            note(2, 200, "nameless.mpeg", "200_200/nameless.mpeg"),
+            note(3, 300, "renamed.mpeg", "300_new_name/renamed.mpeg"),
+            note(4, 500, "other_author.mpeg", "500_eve/other_author.mpeg"),
+            note(5, 100, "lost.mpeg", "100_ana/lost.mpeg"),
+        ];
+
+        assert_eq!(
+            resolve(&entries, &notes),
+            vec![
+                (2, "200_none/nameless.mpeg".to_string()),
+                (3, "300_old_name/renamed.mpeg".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn backfills_the_folder_of_authors_without_username() {
+        // INFO: a single connection, every connection to `:memory:` is a new database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        // INFO: notes written before `media_key` existed
+        let migrator = sqlx::migrate!();
+        let legacy = Migrator {
+            migrations: Cow::Owned(
+                migrator
+                    .migrations
+                    .iter()
+                    .filter(|migration| migration.version < 6)
+                    .cloned()
+                    .collect(),
+            ),
+            ..Migrator::DEFAULT
+        };
+        legacy.run(&pool).await.unwrap();
+
+        // INFO: users without telegram username were saved with their telegram id
+        sqlx::query!(
+            r#"
+INSERT INTO users (id, telegram_id, username, first_name, locale) VALUES
+    (10, 100, 'ana', 'ana', 1),
+    (20, 200, '200', '200', 1)
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            r#"
+INSERT INTO notes (id, user_id, file_name) VALUES
+    (10, 10, 'named.mpeg'),
+    (20, 20, 'nameless.mpeg')
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        migrator.run(&pool).await.unwrap();
+
+        let keys = sqlx::query!(r#"SELECT id, media_key AS "media_key!" FROM notes ORDER BY id"#)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let keys: Vec<(i64, String)> = keys.into_iter().map(|k| (k.id, k.media_key)).collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                (10, "100_ana/named.mpeg".to_string()),
+                (20, "200_none/nameless.mpeg".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::{io::ErrorKind, path::Path};
+
+use anyhow::{anyhow, Context};
+use cheer_up_core::{
+    media::{media_key, MediaStore},
+    user::NewUser,
+};
+use log::{info, warn};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tokio::fs;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Fixtures {
+    users: Vec<NewUser>,
+    groups: Vec<GroupFixture>,
+    notes: Vec<NoteFixture>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GroupFixture {
+    name: String,
+    /// Lowercase, unique among groups
+    invite_code: String,
+    /// Usernames of fixture users, the first one creates the group
+    members: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoteFixture {
+    /// Author, must be one of the fixture users
+    username: String,
+    /// Media file, relative to the fixtures folder
+    file: String,
+}
+
+/// Loads `<fixtures>/seed.toml` into the database, note media goes through `store`
+/// exactly like uploads coming from the bots.
+pub async fn run(pool: &SqlitePool, store: &dyn MediaStore, fixtures: &Path) -> anyhow::Result<()> {
+    let path = fixtures.join("seed.toml");
+    let raw = fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("cannot read seed fixtures {:?}", path))?;
+    let seed: Fixtures =
+        toml::from_str(&raw).with_context(|| format!("cannot parse seed fixtures {:?}", path))?;
+
+    for user in &seed.users {
+        let inserted = sqlx::query!(
+            r#"
+INSERT OR IGNORE INTO users (telegram_id, username, first_name, last_name, locale)
+VALUES (?, ?, ?, ?, (SELECT id FROM locales WHERE language = ?));
+            "#,
+            user.telegram_id,
+            user.username,
+            user.first_name,
+            user.last_name,
+            user.locale
+        )
+        .execute(pool)
+        .await?;
+
+        info!(
+            "[SEED] user {}: {}",
+            user.username,
+            if inserted.rows_affected() > 0 {
+                "created"
+            } else {
+                "already present"
+            }
+        );
+    }
+
+    // INFO: notes are only shared with the groups of their author, users of no
+    //       group would see none of them
+    for group in &seed.groups {
+        let creator = group.members.first();
+        let inserted = sqlx::query!(
+            r#"
+INSERT OR IGNORE INTO groups (name, invite_code, created_by)
+VALUES (?, ?, (SELECT id FROM users WHERE username = ?));
+            "#,
+            group.name,
+            group.invite_code,
+            creator
+        )
+        .execute(pool)
+        .await?;
+
+        info!(
+            "[SEED] group {}: {}",
+            group.name,
+            if inserted.rows_affected() > 0 {
+                "created"
+            } else {
+                "already present"
+            }
+        );
+
+        let group_id = sqlx::query_scalar!(
+            "SELECT id FROM groups WHERE invite_code = ?",
+            group.invite_code
+        )
+        .fetch_one(pool)
+        .await?;
+
+        for member in &group.members {
+            let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE username = ?", member)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "seed group {:?} references unknown user {:?}",
+                        group.name,
+                        member
+                    )
+                })?;
+
+            let joined = sqlx::query!(
+                "INSERT OR IGNORE INTO group_members (group_id, user_id) VALUES (?, ?)",
+                group_id,
+                user_id
+            )
+            .execute(pool)
+            .await?;
+
+            if joined.rows_affected() > 0 {
+                info!("[SEED] user {} joined group {}", member, group.name);
+            }
+        }
+    }
+
+    for note in &seed.notes {
+        let author = sqlx::query!(
+            "SELECT id, telegram_id FROM users WHERE username = ?",
+            note.username
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            anyhow!(
+                "seed note {:?} references unknown user {:?}",
+                note.file,
+                note.username
+            )
+        })?;
+
+        let file_name = Path::new(&note.file)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("seed note {:?} is not a file", note.file))?;
+
+        let existing = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM notes WHERE user_id = ? AND file_name = ?",
+            author.id,
+            file_name
+        )
+        .fetch_one(pool)
+        .await?;
+        if existing > 0 {
+            info!("[SEED] note {}: already present", note.file);
+            continue;
+        }
+
+        let bytes = match fs::read(fixtures.join(&note.file)).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                warn!("[SEED] note {}: media file not found, skipping", note.file);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let key = media_key(author.telegram_id, &note.username, &file_name);
+        store.put(&key, bytes).await?;
+
+        sqlx::query!(
+            "INSERT INTO notes (user_id, file_name, media_key) VALUES (?, ?, ?)",
+            author.id,
+            file_name,
+            key
+        )
+        .execute(pool)
+        .await?;
+
+        info!("[SEED] note {}: stored as {}", note.file, key);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use cheer_up_core::note::{Note, SelectionStrategy};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Age after which a note is half as likely to be picked by [`SelectionStrategy::Recency`].
+pub const RECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+/// Keeps old notes reachable with [`SelectionStrategy::Recency`].
+pub const RECENCY_MIN_WEIGHT: f64 = 0.01;
+/// Hours around the current one counting as "same time of day".
+pub const TIME_OF_DAY_WINDOW_HOURS: i64 = 2;
+/// How much more likely a note uploaded around the current time of day is.
+pub const TIME_OF_DAY_BOOST: f64 = 4.0;
+
+/// A note the requester can receive, along with what the strategies weigh.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub note: Note,
+    /// Days since upload, `None` if the upload time is unknown
+    pub age_days: Option<f64>,
+    /// UTC hour of upload, `None` if the upload time is unknown
+    pub upload_hour: Option<i64>,
+    /// Users having this note in their favorites
+    pub favorites: i64,
+    /// Last time the requester received this note, comparable as text
+    pub last_viewed_at: Option<String>,
+}
+
+/// Random source for [`pick`], seeded runs always pick the same notes.
+pub fn rng(seed: Option<u64>) -> ChaCha8Rng {
+    match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_entropy(),
+    }
+}
+
+/// Index of the chosen candidate, `None` if there are none.
+pub fn pick(
+    strategy: SelectionStrategy,
+    candidates: &[Candidate],
+    now_hour: i64,
+    rng: &mut impl Rng,
+) -> Option<usize> {
+    if candidates.is_empty() {
+        return None;
+    }
+
+    match strategy {
+        SelectionStrategy::ShuffleBag => {
+            // INFO: never seen notes sort first since `None < Some(_)`
+            let oldest = candidates
+                .iter()
+                .map(|c| c.last_viewed_at.as_deref())
+                .min()
+                .flatten();
+            let bag: Vec<usize> = (0..candidates.len())
+                .filter(|&i| candidates[i].last_viewed_at.as_deref() == oldest)
+                .collect();
+
+            Some(bag[rng.gen_range(0..bag.len())])
+        }
+        SelectionStrategy::Uniform => Some(rng.gen_range(0..candidates.len())),
+        SelectionStrategy::Recency => weighted(candidates, rng, |c| {
+            let age = c.age_days.unwrap_or(f64::INFINITY).max(0.0);
+            0.5_f64
+                .powf(age / RECENCY_HALF_LIFE_DAYS)
+                .max(RECENCY_MIN_WEIGHT)
+        }),
+        SelectionStrategy::AuthorFairness => {
+            let mut per_author: HashMap<i64, usize> = HashMap::new();
+            for c in candidates {
+                *per_author.entry(c.note.user_id).or_default() += 1;
+            }
+
+            weighted(candidates, rng, |c| {
+                1.0 / per_author[&c.note.user_id] as f64
+            })
+        }
+        SelectionStrategy::Favorites => weighted(candidates, rng, |c| 1.0 + c.favorites as f64),
+        SelectionStrategy::TimeOfDay => weighted(candidates, rng, |c| match c.upload_hour {
+            Some(hour) => {
+                let distance = (hour - now_hour).rem_euclid(24);
+                if distance.min(24 - distance) <= TIME_OF_DAY_WINDOW_HOURS {
+                    TIME_OF_DAY_BOOST
+                } else {
+                    1.0
+                }
+            }
+            None => 1.0,
+        }),
+    }
+}
+
+fn weighted(
+    candidates: &[Candidate],
+    rng: &mut impl Rng,
+    weight: impl Fn(&Candidate) -> f64,
+) -> Option<usize> {
+    let weights: Vec<f64> = candidates.iter().map(weight).collect();
+    let total: f64 = weights.iter().sum();
+
+    let mut target = rng.gen_range(0.0..total);
+    for (i, w) in weights.iter().enumerate() {
+        if target < *w {
+            return Some(i);
+        }
+        target -= w;
+    }
+
+    // INFO: float rounding can leave `target` a hair above the last weight
+    Some(candidates.len() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: i64, user_id: i64) -> Candidate {
+        Candidate {
+            note: Note {
+                id,
+                user_id,
+                file_name: format!("{}.mp4", id),
+                file_id: None,
+                file_unique_id: None,
+                duration: None,
+                file_size: None,
+                media_key: None,
+                group_id: None,
+                recipient_id: None,
+                caption: None,
+                created_at: None,
+            },
+            age_days: None,
+            upload_hour: None,
+            favorites: 0,
+            last_viewed_at: None,
+        }
+    }
+
+    fn pick_counts(
+        strategy: SelectionStrategy,
+        candidates: &[Candidate],
+        now_hour: i64,
+    ) -> Vec<usize> {
+        let mut rng = rng(Some(42));
+        let mut counts = vec![0; candidates.len()];
+        for _ in 0..10_000 {
+            counts[pick(strategy, candidates, now_hour, &mut rng).unwrap()] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn seeded_picks_are_reproducible() {
+        let candidates: Vec<Candidate> = (0..20).map(|id| candidate(id, id % 3)).collect();
+
+        let run = |seed| {
+            let mut rng = rng(Some(seed));
+            (0..50)
+                .map(|_| pick(SelectionStrategy::Uniform, &candidates, 12, &mut rng).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn shuffle_bag_prefers_unseen_then_least_recently_seen() {
+        let mut candidates: Vec<Candidate> = (0..3).map(|id| candidate(id, 1)).collect();
+        candidates[0].last_viewed_at = Some("2024-03-01 10:00:00.000".to_string());
+        candidates[2].last_viewed_at = Some("2024-03-01 09:00:00.000".to_string());
+
+        let mut rng = rng(Some(1));
+        assert_eq!(
+            pick(SelectionStrategy::ShuffleBag, &candidates, 0, &mut rng),
+            Some(1)
+        );
+
+        candidates[1].last_viewed_at = Some("2024-03-01 11:00:00.000".to_string());
+        assert_eq!(
+            pick(SelectionStrategy::ShuffleBag, &candidates, 0, &mut rng),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn author_fairness_ignores_upload_count() {
+        // INFO: author 1 uploaded 9 notes, author 2 a single one
+        let mut candidates: Vec<Candidate> = (0..9).map(|id| candidate(id, 1)).collect();
+        candidates.push(candidate(9, 2));
+
+        let counts = pick_counts(SelectionStrategy::AuthorFairness, &candidates, 0);
+        let prolific: usize = counts[..9].iter().sum();
+
+        assert!((4_500..5_500).contains(&prolific), "{:?}", counts);
+        assert!((4_500..5_500).contains(&counts[9]), "{:?}", counts);
+    }
+
+    #[test]
+    fn recency_favorites_and_time_of_day_boost_matching_notes() {
+        let mut candidates = vec![candidate(0, 1), candidate(1, 1)];
+        candidates[0].age_days = Some(1.0);
+        candidates[1].age_days = Some(60.0);
+        let counts = pick_counts(SelectionStrategy::Recency, &candidates, 0);
+        assert!(counts[0] > counts[1] * 10, "{:?}", counts);
+
+        let mut candidates = vec![candidate(0, 1), candidate(1, 1)];
+        candidates[0].favorites = 3;
+        let counts = pick_counts(SelectionStrategy::Favorites, &candidates, 0);
+        assert!(counts[0] > counts[1] * 3, "{:?}", counts);
+
+        // INFO: 23:00 is one hour away from 00:00 across midnight
+        let mut candidates = vec![candidate(0, 1), candidate(1, 1)];
+        candidates[0].upload_hour = Some(23);
+        candidates[1].upload_hour = Some(12);
+        let counts = pick_counts(SelectionStrategy::TimeOfDay, &candidates, 0);
+        assert!(counts[0] > counts[1] * 3, "{:?}", counts);
+    }
+}
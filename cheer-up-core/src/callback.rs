@@ -0,0 +1,259 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bincode::Options;
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::Sha256;
+
+/// Telegram rejects inline buttons with longer `callback_data`.
+pub const MAX_CALLBACK_DATA_LEN: usize = 64;
+/// Payloads too long for a button kept in memory, the oldest are dropped first.
+pub const DEFAULT_STORE_CAPACITY: usize = 10_000;
+
+const TAG_LEN: usize = 8;
+// INFO: base64 turns every 3 bytes into 4 chars
+const MAX_RAW_LEN: usize = MAX_CALLBACK_DATA_LEN / 4 * 3;
+
+const KIND_INLINE: u8 = 0;
+const KIND_STORED: u8 = 1;
+
+pub type Result<T, E = CallbackError> = std::result::Result<T, E>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CallbackError {
+    #[error("callback data could not be serialized: {0}")]
+    Encode(#[source] bincode::Error),
+    #[error("callback data is malformed")]
+    Malformed,
+    #[error("callback data signature does not match")]
+    InvalidTag,
+    #[error("callback data expired")]
+    Expired,
+}
+
+static GLOBAL_CODEC: OnceLock<CallbackCodec> = OnceLock::new();
+
+/// Installs the codec returned by [`global`], call it once at startup
+/// before any button is built. Later calls are ignored.
+pub fn init(codec: CallbackCodec) {
+    if GLOBAL_CODEC.set(codec).is_err() {
+        log::warn!("[CHEER_UP_CORE] callback codec already initialized, ignoring");
+    }
+}
+
+/// Process-wide codec installed by [`init`].
+pub fn global() -> &'static CallbackCodec {
+    GLOBAL_CODEC
+        .get()
+        .expect("callback codec must be initialized at startup")
+}
+
+/// Signed, compact encoding of inline button `callback_data`.
+///
+/// Data is serialized with `bincode` varints and signed with a truncated
+/// HMAC-SHA256 tag, so users cannot forge it. Data that does not fit in
+/// [`MAX_CALLBACK_DATA_LEN`] is kept in memory and the button only carries
+/// its signed id, these buttons expire when the bot restarts.
+#[derive(Debug)]
+pub struct CallbackCodec {
+    secret: Vec<u8>,
+    store: Mutex<PayloadStore>,
+}
+
+impl CallbackCodec {
+    pub fn new(secret: &[u8]) -> CallbackCodec {
+        CallbackCodec::with_capacity(secret, DEFAULT_STORE_CAPACITY)
+    }
+
+    pub fn with_capacity(secret: &[u8], capacity: usize) -> CallbackCodec {
+        CallbackCodec {
+            secret: secret.to_vec(),
+            store: Mutex::new(PayloadStore::new(capacity)),
+        }
+    }
+
+    pub fn encode<T: Serialize>(&self, data: &T) -> Result<String> {
+        let body = bincode_options()
+            .serialize(data)
+            .map_err(CallbackError::Encode)?;
+
+        if 1 + body.len() + TAG_LEN <= MAX_RAW_LEN {
+            return Ok(self.sign(KIND_INLINE, &body));
+        }
+
+        log::debug!(
+            "[CALLBACK_CODEC] {} bytes do not fit in a button, storing them",
+            body.len()
+        );
+        let id = self.store.lock().unwrap().insert(body);
+        let body = bincode_options()
+            .serialize(&id)
+            .map_err(CallbackError::Encode)?;
+
+        Ok(self.sign(KIND_STORED, &body))
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, data: &str) -> Result<T> {
+        let raw = URL_SAFE_NO_PAD
+            .decode(data)
+            .map_err(|_| CallbackError::Malformed)?;
+        if raw.len() < 1 + TAG_LEN {
+            return Err(CallbackError::Malformed);
+        }
+
+        let (signed, tag) = raw.split_at(raw.len() - TAG_LEN);
+        self.mac(signed)
+            .verify_truncated_left(tag)
+            .map_err(|_| CallbackError::InvalidTag)?;
+
+        let body = match signed[0] {
+            KIND_INLINE => signed[1..].to_vec(),
+            KIND_STORED => {
+                let id: u64 = bincode_options()
+                    .deserialize(&signed[1..])
+                    .map_err(|_| CallbackError::Malformed)?;
+                self.store
+                    .lock()
+                    .unwrap()
+                    .get(id)
+                    .ok_or(CallbackError::Expired)?
+            }
+            _ => return Err(CallbackError::Malformed),
+        };
+
+        bincode_options()
+            .deserialize(&body)
+            .map_err(|_| CallbackError::Malformed)
+    }
+
+    fn sign(&self, kind: u8, body: &[u8]) -> String {
+        let mut raw = Vec::with_capacity(1 + body.len() + TAG_LEN);
+        raw.push(kind);
+        raw.extend_from_slice(body);
+
+        let tag = self.mac(&raw).finalize().into_bytes();
+        raw.extend_from_slice(&tag[..TAG_LEN]);
+
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    fn mac(&self, data: &[u8]) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("hmac accepts keys of any length");
+        mac.update(data);
+        mac
+    }
+}
+
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_varint_encoding()
+        .reject_trailing_bytes()
+}
+
+#[derive(Debug)]
+struct PayloadStore {
+    capacity: usize,
+    next_id: u64,
+    payloads: HashMap<u64, Vec<u8>>,
+    order: VecDeque<u64>,
+}
+
+impl PayloadStore {
+    fn new(capacity: usize) -> PayloadStore {
+        // INFO: ids start from the current time so that buttons stored before a
+        //       restart never resolve to a payload stored after it
+        let next_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros() as u64)
+            .unwrap_or_default();
+
+        PayloadStore {
+            capacity: capacity.max(1),
+            next_id,
+            payloads: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn insert(&mut self, payload: Vec<u8>) -> u64 {
+        while self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.payloads.remove(&oldest);
+            }
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.payloads.insert(id, payload);
+        self.order.push_back(id);
+
+        id
+    }
+
+    fn get(&self, id: u64) -> Option<Vec<u8>> {
+        self.payloads.get(&id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    enum Payload {
+        Text(String),
+        NoteId(i64),
+    }
+
+    #[test]
+    fn round_trips_and_fits_in_a_button() {
+        let codec = CallbackCodec::new(b"secret");
+
+        for payload in [
+            Payload::NoteId(1234),
+            Payload::Text("short".to_string()),
+            Payload::Text("a username way too long for a telegram button".repeat(3)),
+        ] {
+            let data = codec.encode(&payload).unwrap();
+            assert!(data.len() <= MAX_CALLBACK_DATA_LEN, "{} is too long", data);
+            assert_eq!(codec.decode::<Payload>(&data).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn rejects_forged_and_expired_data() {
+        let codec = CallbackCodec::with_capacity(b"secret", 1);
+
+        let data = codec.encode(&Payload::NoteId(1)).unwrap();
+        let forged = CallbackCodec::new(b"other secret")
+            .encode(&Payload::NoteId(2))
+            .unwrap();
+        assert!(matches!(
+            codec.decode::<Payload>(&forged),
+            Err(CallbackError::InvalidTag)
+        ));
+        assert!(matches!(
+            codec.decode::<Payload>(r#"{"topic":"DeleteNote"}"#),
+            Err(CallbackError::Malformed)
+        ));
+        assert!(codec.decode::<Payload>(&data).is_ok());
+
+        // INFO: the store keeps a single payload, the second one evicts the first
+        let long = Payload::Text("x".repeat(100));
+        let first = codec.encode(&long).unwrap();
+        let second = codec.encode(&long).unwrap();
+        assert!(matches!(
+            codec.decode::<Payload>(&first),
+            Err(CallbackError::Expired)
+        ));
+        assert_eq!(codec.decode::<Payload>(&second).unwrap(), long);
+    }
+}
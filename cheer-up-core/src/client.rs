@@ -0,0 +1,502 @@
+use std::sync::OnceLock;
+
+use log::debug;
+use reqwest::{Client, RequestBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::dialogue::{Dialogue, DialogueBody, NewDialogue};
+use crate::digest::{
+    Digest, DigestListBody, DigestQuery, DigestSent, DigestSettings, DigestSettingsBody, MuteDigest,
+};
+use crate::error::ApiError;
+use crate::group::{Group, GroupBody, GroupListBody, JoinGroup, NewGroup};
+use crate::locale::{Locale, LocaleBody};
+use crate::media::{GcReport, GcReportBody};
+use crate::note::{
+    DeleteNoteQuery, DeleteNotes, NewNote, NewNoteView, Note, NoteBody, NoteListBody,
+    NoteListQuery, NotePage, NoteRecipient, NoteView, NoteViewBody, RandomNoteQuery,
+    SelectionStrategy, StrategyBody,
+};
+use crate::reaction::{NewFavorite, NewReaction, NoteReactions, ReactionListBody, ReactionsBody};
+use crate::stats::{Stats, StatsBody, StatsQuery, UserStats};
+use crate::subscription::{
+    NewSubscription, Subscription, SubscriptionBody, SubscriptionDelivered, SubscriptionListBody,
+};
+use crate::user::{NewUser, User, UserBody, UserListBody};
+
+pub const DEFAULT_BASE_URL: &str = "http://0.0.0.0:1989";
+/// Header carrying the service api key, checked by `cheer-up-api` on every route.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+pub type Result<T, E = ApiError> = std::result::Result<T, E>;
+
+static GLOBAL_CLIENT: OnceLock<CheerUpApiClient> = OnceLock::new();
+
+/// Installs the client returned by [`global`], call it once at startup
+/// before any handler runs. Later calls are ignored.
+pub fn init(client: CheerUpApiClient) {
+    if GLOBAL_CLIENT.set(client).is_err() {
+        log::warn!("[CHEER_UP_CORE] api client already initialized, ignoring");
+    }
+}
+
+/// Process-wide client sharing a single connection pool, falls back to
+/// [`DEFAULT_BASE_URL`] if [`init`] was never called.
+pub fn global() -> &'static CheerUpApiClient {
+    GLOBAL_CLIENT.get_or_init(|| CheerUpApiClient::new(DEFAULT_BASE_URL))
+}
+
+/// Typed async client for `cheer-up-api` endpoints.
+///
+/// Cloning is cheap, clones share the same underlying connection pool.
+#[derive(Debug, Clone)]
+pub struct CheerUpApiClient {
+    http: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Default for CheerUpApiClient {
+    fn default() -> Self {
+        CheerUpApiClient::new(DEFAULT_BASE_URL)
+    }
+}
+
+impl CheerUpApiClient {
+    pub fn new(base_url: impl Into<String>) -> CheerUpApiClient {
+        CheerUpApiClient::with_http_client(Client::new(), base_url)
+    }
+
+    pub fn with_http_client(http: Client, base_url: impl Into<String>) -> CheerUpApiClient {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+
+        CheerUpApiClient {
+            http,
+            base_url,
+            api_key: None,
+        }
+    }
+
+    /// Sends `api_key` with every request, see [`API_KEY_HEADER`].
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> CheerUpApiClient {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let request = match &self.api_key {
+            Some(api_key) => request.header(API_KEY_HEADER, api_key),
+            None => request,
+        };
+
+        let resp = request.send().await?;
+        let status = resp.status();
+
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            debug!(
+                "[CHEER_UP_API_CLIENT] status {} with body: {}",
+                status, body
+            );
+            return Err(ApiError::from_response(status, &body));
+        }
+
+        Ok(resp.json::<T>().await?)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.http.get(self.url(path))).await
+    }
+
+    async fn get_with_query<Q: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.send(self.http.get(self.url(path)).query(query)).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        self.send(self.http.post(self.url(path)).json(body)).await
+    }
+
+    async fn patch<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        self.send(self.http.patch(self.url(path)).json(body)).await
+    }
+
+    async fn put<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        self.send(self.http.put(self.url(path)).json(body)).await
+    }
+
+    async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.http.delete(self.url(path))).await
+    }
+
+    async fn delete_with_query<Q: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.send(self.http.delete(self.url(path)).query(query))
+            .await
+    }
+
+    async fn delete_with_body<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        self.send(self.http.delete(self.url(path)).json(body)).await
+    }
+
+    // INFO: users
+
+    pub async fn list_users(&self) -> Result<Vec<User>> {
+        let body: UserListBody<User> = self.get("users").await?;
+        Ok(body.users)
+    }
+
+    pub async fn get_user(&self, user_id: i64) -> Result<User> {
+        let body: UserBody<User> = self.get(&format!("users/{}", user_id)).await?;
+        Ok(body.user)
+    }
+
+    pub async fn get_user_by_username(&self, username: &str) -> Result<User> {
+        let body: UserBody<User> = self.get(&format!("users/name/{}", username)).await?;
+        Ok(body.user)
+    }
+
+    pub async fn get_user_by_telegram_id(&self, telegram_id: i64) -> Result<User> {
+        let body: UserBody<User> = self.get(&format!("users/telegram/{}", telegram_id)).await?;
+        Ok(body.user)
+    }
+
+    /// Registers the user, or refreshes the names of an already registered one.
+    pub async fn upsert_user(&self, user: &NewUser) -> Result<User> {
+        let body: UserBody<User> = self
+            .put(&format!("users/telegram/{}", user.telegram_id), user)
+            .await?;
+        Ok(body.user)
+    }
+
+    pub async fn create_user(&self, new_user: &NewUser) -> Result<User> {
+        let body: UserBody<User> = self.post("users", new_user).await?;
+        Ok(body.user)
+    }
+
+    pub async fn delete_user(&self, user_id: i64) -> Result<String> {
+        let body: UserBody<String> = self.delete(&format!("users/{}", user_id)).await?;
+        Ok(body.user)
+    }
+
+    // INFO: notes
+
+    pub async fn get_note(&self, note_id: i64) -> Result<Note> {
+        let body: NoteBody<Note> = self.get(&format!("notes/{}", note_id)).await?;
+        Ok(body.note)
+    }
+
+    pub async fn list_notes(&self, query: &NoteListQuery) -> Result<Vec<Note>> {
+        let body: NoteListBody<Note> = self.get_with_query("notes", query).await?;
+        Ok(body.notes)
+    }
+
+    /// Same as [`Self::list_notes`], with the position of the page among the matching notes.
+    pub async fn list_notes_page(&self, query: &NoteListQuery) -> Result<NotePage> {
+        self.get_with_query("notes", query).await
+    }
+
+    pub async fn list_user_notes(&self, user_id: i64) -> Result<Vec<Note>> {
+        let body: NoteListBody<Note> = self.get(&format!("notes/user/{}", user_id)).await?;
+        Ok(body.notes)
+    }
+
+    pub async fn random_note(&self, query: &RandomNoteQuery) -> Result<Note> {
+        let body: NoteBody<Note> = self.get_with_query("notes/random", query).await?;
+        Ok(body.note)
+    }
+
+    /// Marks the note as seen by the user, see [`RandomNoteQuery`].
+    pub async fn record_note_view(&self, note_id: i64, view: &NewNoteView) -> Result<NoteView> {
+        let body: NoteViewBody<NoteView> =
+            self.post(&format!("notes/{}/views", note_id), view).await?;
+        Ok(body.view)
+    }
+
+    pub async fn create_note(&self, new_note: &NewNote) -> Result<Note> {
+        let body: NoteBody<Note> = self.post("notes", new_note).await?;
+        Ok(body.note)
+    }
+
+    /// Fails with a not found [`ApiError`] if the recipient shares no group with the author.
+    pub async fn set_note_recipient(
+        &self,
+        note_id: i64,
+        recipient: &NoteRecipient,
+    ) -> Result<Note> {
+        let body: NoteBody<Note> = self
+            .put(&format!("notes/{}/recipient", note_id), recipient)
+            .await?;
+        Ok(body.note)
+    }
+
+    /// Fails with a forbidden [`ApiError`] unless `acting_user` owns the note or is an admin.
+    pub async fn delete_note(&self, note_id: i64, acting_user: i64) -> Result<String> {
+        let query = DeleteNoteQuery { acting_user };
+        let body: NoteBody<String> = self
+            .delete_with_query(&format!("notes/{}", note_id), &query)
+            .await?;
+        Ok(body.note)
+    }
+
+    /// Fails with a forbidden [`ApiError`] unless `acting_user` is `user_id` or an admin.
+    pub async fn delete_user_notes(&self, user_id: i64, acting_user: i64) -> Result<String> {
+        let query = DeleteNoteQuery { acting_user };
+        let body: NoteBody<String> = self
+            .delete_with_query(&format!("notes/user/{}", user_id), &query)
+            .await?;
+        Ok(body.note)
+    }
+
+    /// Returns the ids of the notes deleted, ids of notes that do not exist are skipped.
+    /// Fails with a forbidden [`ApiError`] unless `acting_user` owns every note or is an admin.
+    pub async fn delete_notes(&self, note_ids: &[i64], acting_user: i64) -> Result<Vec<i64>> {
+        let delete = DeleteNotes {
+            note_ids: note_ids.to_vec(),
+            acting_user,
+        };
+        let body: NoteListBody<i64> = self.delete_with_body("notes", &delete).await?;
+        Ok(body.notes)
+    }
+
+    // INFO: reactions & favorites
+
+    /// Replaces any previous reaction of the same user to the note.
+    pub async fn react_to_note(
+        &self,
+        note_id: i64,
+        reaction: &NewReaction,
+    ) -> Result<NoteReactions> {
+        let body: ReactionsBody<NoteReactions> = self
+            .post(&format!("notes/{}/reactions", note_id), reaction)
+            .await?;
+        Ok(body.reactions)
+    }
+
+    pub async fn get_note_reactions(&self, note_id: i64) -> Result<NoteReactions> {
+        let body: ReactionsBody<NoteReactions> =
+            self.get(&format!("notes/{}/reactions", note_id)).await?;
+        Ok(body.reactions)
+    }
+
+    /// Reactions to every note uploaded by `user_id`.
+    pub async fn list_user_notes_reactions(&self, user_id: i64) -> Result<Vec<NoteReactions>> {
+        let body: ReactionListBody<NoteReactions> = self
+            .get(&format!("notes/user/{}/reactions", user_id))
+            .await?;
+        Ok(body.reactions)
+    }
+
+    pub async fn list_user_favorites(&self, user_id: i64) -> Result<Vec<Note>> {
+        let body: NoteListBody<Note> = self.get(&format!("users/{}/favorites", user_id)).await?;
+        Ok(body.notes)
+    }
+
+    /// Fails with a not found [`ApiError`] if the note is not shared with the user.
+    pub async fn add_user_favorite(&self, user_id: i64, favorite: &NewFavorite) -> Result<Note> {
+        let body: NoteBody<Note> = self
+            .post(&format!("users/{}/favorites", user_id), favorite)
+            .await?;
+        Ok(body.note)
+    }
+
+    // INFO: groups
+
+    pub async fn create_group(&self, new_group: &NewGroup) -> Result<Group> {
+        let body: GroupBody<Group> = self.post("groups", new_group).await?;
+        Ok(body.group)
+    }
+
+    pub async fn list_user_groups(&self, user_id: i64) -> Result<Vec<Group>> {
+        let body: GroupListBody<Group> = self.get(&format!("groups/user/{}", user_id)).await?;
+        Ok(body.groups)
+    }
+
+    /// Users sharing at least one group with `user_id`, the user excluded.
+    pub async fn list_user_peers(&self, user_id: i64) -> Result<Vec<User>> {
+        let body: UserListBody<User> = self.get(&format!("groups/user/{}/peers", user_id)).await?;
+        Ok(body.users)
+    }
+
+    /// Fails with a not found [`ApiError`] if no group has this invite code.
+    pub async fn join_group(&self, join: &JoinGroup) -> Result<Group> {
+        let body: GroupBody<Group> = self.post("groups/join", join).await?;
+        Ok(body.group)
+    }
+
+    // INFO: selection strategy
+
+    pub async fn get_user_strategy(&self, user_id: i64) -> Result<Option<SelectionStrategy>> {
+        let body: StrategyBody<Option<SelectionStrategy>> =
+            self.get(&format!("strategy/{}", user_id)).await?;
+        Ok(body.strategy)
+    }
+
+    /// `None` resets the user to the deployment default.
+    pub async fn set_user_strategy(
+        &self,
+        user_id: i64,
+        strategy: Option<SelectionStrategy>,
+    ) -> Result<Option<SelectionStrategy>> {
+        let body: StrategyBody<Option<SelectionStrategy>> = self
+            .patch(&format!("strategy/{}", user_id), &StrategyBody { strategy })
+            .await?;
+        Ok(body.strategy)
+    }
+
+    // INFO: digests
+
+    /// Users without settings get the default ones, muted.
+    pub async fn get_digest_settings(&self, user_id: i64) -> Result<DigestSettings> {
+        let body: DigestSettingsBody<DigestSettings> =
+            self.get(&format!("digests/settings/{}", user_id)).await?;
+        Ok(body.settings)
+    }
+
+    pub async fn set_digest_muted(&self, user_id: i64, muted: bool) -> Result<DigestSettings> {
+        let body: DigestSettingsBody<DigestSettings> = self
+            .patch(
+                &format!("digests/settings/{}", user_id),
+                &MuteDigest { muted },
+            )
+            .await?;
+        Ok(body.settings)
+    }
+
+    /// Digests of the unmuted users whose last one is older than the queried period.
+    pub async fn list_due_digests(&self, query: &DigestQuery) -> Result<Vec<Digest>> {
+        let body: DigestListBody<Digest> = self.get_with_query("digests", query).await?;
+        Ok(body.digests)
+    }
+
+    /// Marks the digest as delivered, the next one starts at `digest.until`.
+    pub async fn mark_digest_sent(&self, digest: &Digest) -> Result<DigestSettings> {
+        let sent = DigestSent {
+            until: digest.until.clone(),
+        };
+
+        let body: DigestSettingsBody<DigestSettings> = self
+            .post(&format!("digests/{}/sent", digest.user_id), &sent)
+            .await?;
+        Ok(body.settings)
+    }
+
+    // INFO: subscriptions
+
+    /// Fails with a not found [`ApiError`] if the user has no schedule.
+    pub async fn get_subscription(&self, user_id: i64) -> Result<Subscription> {
+        let body: SubscriptionBody<Subscription> =
+            self.get(&format!("subscriptions/{}", user_id)).await?;
+        Ok(body.subscription)
+    }
+
+    pub async fn set_subscription(
+        &self,
+        user_id: i64,
+        subscription: &NewSubscription,
+    ) -> Result<Subscription> {
+        let body: SubscriptionBody<Subscription> = self
+            .put(&format!("subscriptions/{}", user_id), subscription)
+            .await?;
+        Ok(body.subscription)
+    }
+
+    pub async fn delete_subscription(&self, user_id: i64) -> Result<String> {
+        let body: SubscriptionBody<String> =
+            self.delete(&format!("subscriptions/{}", user_id)).await?;
+        Ok(body.subscription)
+    }
+
+    /// Subscriptions whose next delivery is now or already passed.
+    pub async fn list_due_subscriptions(&self) -> Result<Vec<Subscription>> {
+        let body: SubscriptionListBody<Subscription> = self.get("subscriptions/due").await?;
+        Ok(body.subscriptions)
+    }
+
+    pub async fn mark_subscription_delivered(
+        &self,
+        user_id: i64,
+        delivered: &SubscriptionDelivered,
+    ) -> Result<Subscription> {
+        let body: SubscriptionBody<Subscription> = self
+            .post(&format!("subscriptions/{}/delivered", user_id), delivered)
+            .await?;
+        Ok(body.subscription)
+    }
+
+    // INFO: dialogues
+
+    /// Fails with a not found [`ApiError`] if the chat is not in the middle of a flow.
+    pub async fn get_dialogue(&self, chat_id: i64) -> Result<Dialogue> {
+        let body: DialogueBody<Dialogue> = self.get(&format!("dialogues/{}", chat_id)).await?;
+        Ok(body.dialogue)
+    }
+
+    pub async fn set_dialogue(&self, chat_id: i64, dialogue: &NewDialogue) -> Result<Dialogue> {
+        let body: DialogueBody<Dialogue> = self
+            .put(&format!("dialogues/{}", chat_id), dialogue)
+            .await?;
+        Ok(body.dialogue)
+    }
+
+    pub async fn delete_dialogue(&self, chat_id: i64) -> Result<String> {
+        let body: DialogueBody<String> = self.delete(&format!("dialogues/{}", chat_id)).await?;
+        Ok(body.dialogue)
+    }
+
+    // INFO: media
+
+    /// Deletes media files no note refers to, reports them along with notes whose file is missing.
+    pub async fn collect_media_garbage(&self) -> Result<GcReport> {
+        let body: GcReportBody<GcReport> = self.post("media/gc", &()).await?;
+        Ok(body.gc)
+    }
+
+    // INFO: locale
+
+    pub async fn get_user_locale(&self, user_id: i64) -> Result<Locale> {
+        let body: LocaleBody<Locale> = self.get(&format!("locale/{}", user_id)).await?;
+        Ok(body.locale)
+    }
+
+    pub async fn set_user_locale(&self, user_id: i64, locale: &Locale) -> Result<Locale> {
+        let new_locale = LocaleBody {
+            locale: locale.clone(),
+        };
+
+        let body: LocaleBody<Locale> = self
+            .patch(&format!("locale/{}", user_id), &new_locale)
+            .await?;
+        Ok(body.locale)
+    }
+
+    // INFO: stats
+
+    pub async fn get_stats(&self, query: &StatsQuery) -> Result<Stats> {
+        let body: StatsBody<Stats> = self.get_with_query("stats", query).await?;
+        Ok(body.stats)
+    }
+
+    pub async fn get_user_stats(&self, user_id: i64) -> Result<UserStats> {
+        let body: StatsBody<UserStats> = self.get(&format!("stats/user/{}", user_id)).await?;
+        Ok(body.stats)
+    }
+}
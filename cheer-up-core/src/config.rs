@@ -0,0 +1,375 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, ValueEnum};
+use log::info;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::digest::DigestQuery;
+use crate::locale::Locale;
+use crate::media::{MediaConfig, S3Config};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("cannot read config file {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("cannot parse config file {path:?}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("missing required setting `{0}` (set it in the config file, as env variable or as cli flag)")]
+    Missing(&'static str),
+    #[error("invalid value for setting `{key}`: {reason}")]
+    Invalid { key: &'static str, reason: String },
+}
+
+/// Reads a TOML config file, a missing file yields `T::default()` so that
+/// env variables and cli flags alone are enough to configure a binary.
+pub fn read_toml<T: DeserializeOwned + Default>(path: &Path) -> Result<T, ConfigError> {
+    if !path.exists() {
+        info!(
+            "[CONFIG] {:?} not found, using env and cli settings only",
+            path
+        );
+        return Ok(T::default());
+    }
+
+    let raw = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    toml::from_str(&raw).map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Picks the highest priority value (cli flag or env variable, then config file)
+/// and fails with a readable error if the setting is nowhere to be found.
+pub fn required(
+    key: &'static str,
+    cli_or_env: Option<String>,
+    file: Option<String>,
+) -> Result<String, ConfigError> {
+    cli_or_env
+        .or(file)
+        .filter(|value| !value.trim().is_empty())
+        .ok_or(ConfigError::Missing(key))
+}
+
+pub fn validate_url(key: &'static str, url: &str) -> Result<(), ConfigError> {
+    reqwest::Url::parse(url)
+        .map(|_| ())
+        .map_err(|e| ConfigError::Invalid {
+            key,
+            reason: format!("{:?} is not a valid url ({})", url, e),
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credits {
+    pub author: String,
+    pub profile_name: String,
+    pub profile_url: String,
+    pub repo_url: String,
+}
+
+/// How stored video notes are sent.
+///
+/// Every note is copied into the media store in both modes, telegram file ids
+/// only work for the bot that received the upload and notes are sent by both bots.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageMode {
+    /// Send the media store copy of every note
+    #[default]
+    #[serde(alias = "local")]
+    #[value(alias = "local")]
+    Media,
+    /// Resend by telegram `file_id`, the media store copy is used as fallback
+    /// when telegram rejects the id (i.e. a note uploaded through the other bot)
+    #[value(name = "file_id")]
+    FileId,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaBackend {
+    /// Files below `media.root`
+    #[default]
+    Local,
+    /// S3-compatible bucket configured in `[media.s3]`
+    S3,
+}
+
+/// Media storage cli flags, flattened into the args of every binary using a [`MediaStore`].
+///
+/// [`MediaStore`]: crate::media::MediaStore
+#[derive(Debug, Args)]
+pub struct MediaArgs {
+    /// Media storage backend
+    #[arg(long, env = "MEDIA_BACKEND", value_enum)]
+    pub media_backend: Option<MediaBackend>,
+    /// Root folder of the local media backend
+    #[arg(long, env = "MEDIA_ROOT")]
+    pub media_root: Option<PathBuf>,
+    /// S3 endpoint url, i.e. `http://localhost:9000`
+    #[arg(long, env = "S3_ENDPOINT")]
+    pub s3_endpoint: Option<String>,
+    /// S3 bucket holding the videonotes
+    #[arg(long, env = "S3_BUCKET")]
+    pub s3_bucket: Option<String>,
+    /// S3 region
+    #[arg(long, env = "S3_REGION")]
+    pub s3_region: Option<String>,
+    /// S3 access key
+    #[arg(long, env = "S3_ACCESS_KEY")]
+    pub s3_access_key: Option<String>,
+    /// S3 secret key
+    #[arg(long, env = "S3_SECRET_KEY", hide_env_values = true)]
+    pub s3_secret_key: Option<String>,
+}
+
+impl MediaConfig {
+    pub const DEFAULT_S3_REGION: &'static str = "us-east-1";
+
+    /// Resolves the `[media]` settings, the local backend stores files below
+    /// `default_root` unless `media.root` is set.
+    pub fn load(
+        args: MediaArgs,
+        file: MediaFile,
+        default_root: PathBuf,
+    ) -> Result<MediaConfig, ConfigError> {
+        let media = match args.media_backend.or(file.backend).unwrap_or_default() {
+            MediaBackend::Local => MediaConfig::Local {
+                root: args.media_root.or(file.root).unwrap_or(default_root),
+            },
+            MediaBackend::S3 => {
+                let s3 = file.s3;
+                let config = S3Config {
+                    endpoint: required("media.s3.endpoint", args.s3_endpoint, s3.endpoint)?,
+                    bucket: required("media.s3.bucket", args.s3_bucket, s3.bucket)?,
+                    region: args
+                        .s3_region
+                        .or(s3.region)
+                        .unwrap_or(MediaConfig::DEFAULT_S3_REGION.to_string()),
+                    access_key: required("media.s3.access_key", args.s3_access_key, s3.access_key)?,
+                    secret_key: required("media.s3.secret_key", args.s3_secret_key, s3.secret_key)?,
+                };
+                validate_url("media.s3.endpoint", &config.endpoint)?;
+
+                MediaConfig::S3(config)
+            }
+        };
+
+        Ok(media)
+    }
+}
+
+/// Settings shared by `cheer-up` and `cheer-up-manager`.
+#[derive(Debug, Clone)]
+pub struct BotConfig {
+    pub api_url: String,
+    /// Sent to `cheer-up-api` with every request
+    pub api_key: String,
+    /// Signs inline button data, see [`crate::callback::CallbackCodec`]
+    pub callback_secret: String,
+    pub telegram_token: String,
+    pub default_locale: Locale,
+    pub data_folder: PathBuf,
+    pub storage_mode: StorageMode,
+    pub media: MediaConfig,
+    pub credits: Credits,
+    /// Days between two digests sent to the same author, only used by `cheer-up-manager`
+    pub digest_period_days: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BotConfigFile {
+    api_url: Option<String>,
+    api_key: Option<String>,
+    callback_secret: Option<String>,
+    telegram_token: Option<String>,
+    default_locale: Option<String>,
+    data_folder: Option<PathBuf>,
+    storage_mode: Option<StorageMode>,
+    media: MediaFile,
+    credits: CreditsFile,
+    digest_period_days: Option<u32>,
+}
+
+/// `[media]` section of a config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MediaFile {
+    backend: Option<MediaBackend>,
+    root: Option<PathBuf>,
+    s3: S3File,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct S3File {
+    endpoint: Option<String>,
+    bucket: Option<String>,
+    region: Option<String>,
+    access_key: Option<String>,
+    secret_key: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CreditsFile {
+    author: Option<String>,
+    profile_name: Option<String>,
+    profile_url: Option<String>,
+    repo_url: Option<String>,
+}
+
+/// Bot cli flags, every flag can also be set through the env variable
+/// shown in `--help` (cli flag > env variable > config file > default).
+#[derive(Debug, Args)]
+pub struct BotArgs {
+    /// Path to the TOML config file
+    #[arg(long, env = "CHEER_UP_CONFIG", default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Base url of cheer-up-api
+    #[arg(long, env = "CHEER_UP_API_URL")]
+    pub api_url: Option<String>,
+    /// Api key of this bot, as configured in cheer-up-api
+    #[arg(long, env = "CHEER_UP_API_KEY", hide_env_values = true)]
+    pub api_key: Option<String>,
+    /// Secret signing inline button data, must differ from the api key
+    #[arg(long, env = "CHEER_UP_CALLBACK_SECRET", hide_env_values = true)]
+    pub callback_secret: Option<String>,
+    /// Telegram bot token
+    #[arg(long, env = "TELOXIDE_TOKEN", hide_env_values = true)]
+    pub telegram_token: Option<String>,
+    /// Default bot language
+    #[arg(long, env = "LOCALE")]
+    pub default_locale: Option<String>,
+    /// Folder holding shared data (i.e. `videonotes/`)
+    #[arg(long, env = "COMMON_DATA_FOLDER")]
+    pub data_folder: Option<PathBuf>,
+    /// How uploaded video notes are stored
+    #[arg(long, env = "STORAGE_MODE", value_enum)]
+    pub storage_mode: Option<StorageMode>,
+    #[command(flatten)]
+    pub media: MediaArgs,
+    /// Author name shown in credits page
+    #[arg(long, env = "AUTHOR")]
+    pub author: Option<String>,
+    /// Author profile name shown in credits page
+    #[arg(long, env = "AUTHOR_PROFILE_NAME")]
+    pub author_profile_name: Option<String>,
+    /// Author profile url shown in credits page
+    #[arg(long, env = "AUTHOR_PROFILE_URL")]
+    pub author_profile_url: Option<String>,
+    /// Code repository url shown in credits and language pages
+    #[arg(long, env = "CODE_REPO_URL")]
+    pub code_repo_url: Option<String>,
+    /// Days between two digests of views and reactions sent to an author
+    #[arg(long, env = "DIGEST_PERIOD_DAYS")]
+    pub digest_period_days: Option<u32>,
+}
+
+impl BotConfig {
+    pub const DEFAULT_DATA_FOLDER: &'static str = "../_common_data";
+
+    pub fn load(args: BotArgs) -> Result<BotConfig, ConfigError> {
+        let file: BotConfigFile = read_toml(&args.config)?;
+
+        let api_url = args
+            .api_url
+            .or(file.api_url)
+            .unwrap_or(crate::client::DEFAULT_BASE_URL.to_string());
+        validate_url("api_url", &api_url)?;
+
+        let api_key = required("api_key", args.api_key, file.api_key)?;
+
+        let callback_secret = required(
+            "callback_secret",
+            args.callback_secret,
+            file.callback_secret,
+        )?;
+        // INFO: leaking one must not give away the other
+        if callback_secret == api_key {
+            return Err(ConfigError::Invalid {
+                key: "callback_secret",
+                reason: "must differ from api_key".to_string(),
+            });
+        }
+
+        let telegram_token = required("telegram_token", args.telegram_token, file.telegram_token)?;
+
+        let default_locale = match args.default_locale.or(file.default_locale) {
+            Some(code) => code.parse::<Locale>().map_err(|e| ConfigError::Invalid {
+                key: "default_locale",
+                reason: e.to_string(),
+            })?,
+            None => Locale::default(),
+        };
+
+        let data_folder = args
+            .data_folder
+            .or(file.data_folder)
+            .unwrap_or(PathBuf::from(BotConfig::DEFAULT_DATA_FOLDER));
+
+        let storage_mode = args.storage_mode.or(file.storage_mode).unwrap_or_default();
+
+        let media = MediaConfig::load(args.media, file.media, data_folder.join("videonotes"))?;
+
+        let credits = Credits {
+            author: required("credits.author", args.author, file.credits.author)?,
+            profile_name: required(
+                "credits.profile_name",
+                args.author_profile_name,
+                file.credits.profile_name,
+            )?,
+            profile_url: required(
+                "credits.profile_url",
+                args.author_profile_url,
+                file.credits.profile_url,
+            )?,
+            repo_url: required(
+                "credits.repo_url",
+                args.code_repo_url,
+                file.credits.repo_url,
+            )?,
+        };
+        validate_url("credits.profile_url", &credits.profile_url)?;
+        validate_url("credits.repo_url", &credits.repo_url)?;
+
+        let digest_period_days = args
+            .digest_period_days
+            .or(file.digest_period_days)
+            .unwrap_or(DigestQuery::DEFAULT_PERIOD_DAYS);
+        if digest_period_days == 0 {
+            return Err(ConfigError::Invalid {
+                key: "digest_period_days",
+                reason: "must be at least 1 day".to_string(),
+            });
+        }
+
+        Ok(BotConfig {
+            api_url,
+            api_key,
+            callback_secret,
+            telegram_token,
+            default_locale,
+            data_folder,
+            storage_mode,
+            media,
+            credits,
+            digest_period_days,
+        })
+    }
+}
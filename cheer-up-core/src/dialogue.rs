@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Step a chat is at in a multi-step flow of a bot, i.e. uploading a note.
+///
+/// The api keeps `state` as is, each bot stores its own state type serialized
+/// to JSON so that flows survive bot restarts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Dialogue {
+    /// Telegram chat id
+    pub chat_id: i64,
+    pub state: String,
+    /// UTC `YYYY-MM-DD HH:MM:SS`
+    pub updated_at: String,
+}
+
+/// Body of `PUT /api/dialogues/:chat_id`, replaces the previous state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewDialogue {
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DialogueBody<T> {
+    pub dialogue: T,
+}
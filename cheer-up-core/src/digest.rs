@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Locale;
+
+/// Whether a user gets digests about their notes, digests are opt-in so
+/// users without settings are muted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct DigestSettings {
+    pub user_id: i64,
+    pub muted: bool,
+    /// End of the period covered by the last digest
+    pub last_sent_at: Option<String>,
+}
+
+/// Body of `PATCH /api/digests/settings/:user_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuteDigest {
+    pub muted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DigestSettingsBody<T> {
+    pub settings: T,
+}
+
+/// Query of `GET /api/digests`, users get a digest at most every `period_days`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestQuery {
+    pub period_days: u32,
+}
+
+impl Default for DigestQuery {
+    fn default() -> DigestQuery {
+        DigestQuery {
+            period_days: DigestQuery::DEFAULT_PERIOD_DAYS,
+        }
+    }
+}
+
+impl DigestQuery {
+    pub const DEFAULT_PERIOD_DAYS: u32 = 7;
+}
+
+/// How friends received the notes of a user between `since` and `until`,
+/// along with what is needed to message the author.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Digest {
+    pub user_id: i64,
+    pub telegram_id: i64,
+    pub locale: Locale,
+    pub since: String,
+    pub until: String,
+    /// Times friends were served one of the notes, the author views excluded
+    pub views: i64,
+    pub heart: i64,
+    pub laugh: i64,
+    pub cry: i64,
+}
+
+impl Digest {
+    pub fn is_empty(&self) -> bool {
+        self.views + self.heart + self.laugh + self.cry == 0
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DigestListBody<T> {
+    pub digests: Vec<T>,
+}
+
+/// Body of `POST /api/digests/:user_id/sent`, `until` of the delivered digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestSent {
+    pub until: String,
+}
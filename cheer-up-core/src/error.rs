@@ -0,0 +1,148 @@
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable reason of a `cheer-up-api` error, see [`ErrorBody`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    NotFound,
+    NoteNotFound,
+    UserNotFound,
+    GroupNotFound,
+    SubscriptionNotFound,
+    DigestSettingsNotFound,
+    DialogueNotFound,
+    Unauthorized,
+    Forbidden,
+    /// Only the author of a note or an admin can do this
+    NotNoteOwner,
+    AlreadyExists,
+    InvalidRequest,
+    InternalError,
+    /// Sent by a newer api, or a response that is not an [`ErrorBody`]
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::NotFound
+                | ErrorCode::NoteNotFound
+                | ErrorCode::UserNotFound
+                | ErrorCode::GroupNotFound
+                | ErrorCode::SubscriptionNotFound
+                | ErrorCode::DigestSettingsNotFound
+                | ErrorCode::DialogueNotFound
+        )
+    }
+}
+
+/// Body of every `cheer-up-api` error response,
+/// i.e. `{"error": {"code": "note_not_found", "message": "note not found"}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub error: ErrorDetails,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorDetails {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ApiError {
+    #[error("request to cheer-up-api failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("cheer-up-api responded with status {status} ({code:?}): {message}")]
+    Status {
+        status: StatusCode,
+        code: ErrorCode,
+        message: String,
+    },
+}
+
+impl ApiError {
+    /// Decodes an error response, bodies that are not an [`ErrorBody`] get
+    /// [`ErrorCode::Unknown`] and are kept as the message.
+    pub fn from_response(status: StatusCode, body: &str) -> ApiError {
+        match serde_json::from_str::<ErrorBody>(body) {
+            Ok(ErrorBody { error }) => ApiError::Status {
+                status,
+                code: error.code,
+                message: error.message,
+            },
+            Err(_) => ApiError::Status {
+                status,
+                code: ErrorCode::Unknown,
+                message: body.to_string(),
+            },
+        }
+    }
+
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Request(e) => e.status(),
+            Self::Status { status, .. } => Some(*status),
+        }
+    }
+
+    /// `None` if the api could not be reached.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Self::Request(_) => None,
+            Self::Status { code, .. } => Some(*code),
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    pub fn is_forbidden(&self) -> bool {
+        self.status() == Some(StatusCode::FORBIDDEN)
+    }
+
+    /// The request was refused because of what it asked, retrying it as is fails again.
+    pub fn is_client_error(&self) -> bool {
+        self.status().is_some_and(|status| status.is_client_error())
+    }
+}
+
+// INFO: lets bot handlers use `?` on api calls inside `ResponseResult` functions
+#[cfg(feature = "teloxide")]
+impl From<ApiError> for teloxide_core::RequestError {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::Request(e) => e.into(),
+            other => teloxide_core::RequestError::Io(std::io::Error::other(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_error_envelopes() {
+        let err = ApiError::from_response(
+            StatusCode::FORBIDDEN,
+            r#"{"error":{"code":"not_note_owner","message":"only the author can"}}"#,
+        );
+        assert_eq!(err.code(), Some(ErrorCode::NotNoteOwner));
+        assert!(err.is_forbidden());
+
+        let err = ApiError::from_response(
+            StatusCode::NOT_FOUND,
+            r#"{"error":{"code":"some_future_code","message":"?"}}"#,
+        );
+        assert_eq!(err.code(), Some(ErrorCode::Unknown));
+
+        let err = ApiError::from_response(StatusCode::BAD_GATEWAY, "upstream down");
+        assert_eq!(err.code(), Some(ErrorCode::Unknown));
+        assert!(err.to_string().contains("upstream down"));
+    }
+}
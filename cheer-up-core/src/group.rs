@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// An isolated circle of friends, users only receive notes shared in their groups.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Group {
+    pub id: i64,
+    pub name: String,
+    pub invite_code: String,
+}
+
+/// Creates a group, `created_by` becomes its first member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewGroup {
+    pub name: String,
+    pub created_by: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinGroup {
+    pub user_id: i64,
+    pub invite_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupBody<T> {
+    pub group: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupListBody<T> {
+    pub groups: Vec<T>,
+}
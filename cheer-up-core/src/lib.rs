@@ -1,14 +1,20 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+pub mod callback;
+pub mod client;
+pub mod config;
+pub mod dialogue;
+pub mod digest;
+pub mod error;
+pub mod group;
+pub mod locale;
+pub mod media;
+pub mod nav;
+pub mod note;
+pub mod reaction;
+pub mod stats;
+pub mod subscription;
+#[cfg(feature = "translations")]
+pub mod translations;
+pub mod user;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub use client::CheerUpApiClient;
+pub use error::{ApiError, ErrorCode};
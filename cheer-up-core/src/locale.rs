@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocaleBody<T> {
+    pub locale: T,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("unsupported locale: {0}")]
+pub struct UnsupportedLocale(pub String);
+
+// INFO: single source of truth for supported languages, adding a language
+//      here makes it available to serde, sqlx, `Display` and `FromStr`
+macro_rules! locales {
+    ($($(#[$attr:meta])* $variant:ident => $code:literal),+ $(,)?) => {
+        #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+        pub enum Locale {
+            $(
+                $(#[$attr])*
+                #[serde(rename = $code)]
+                #[cfg_attr(feature = "sqlx", sqlx(rename = $code))]
+                $variant,
+            )+
+        }
+
+        impl Locale {
+            pub const ALL: &'static [Locale] = &[$(Locale::$variant),+];
+
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $(Locale::$variant => $code,)+
+                }
+            }
+        }
+    };
+}
+
+locales! {
+    #[default]
+    EN => "en",
+    ES => "es",
+    IT => "it",
+    UA => "ua",
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = UnsupportedLocale;
+
+    fn from_str(locale: &str) -> Result<Self, Self::Err> {
+        Locale::ALL
+            .iter()
+            .find(|supported| supported.code() == locale)
+            .cloned()
+            .ok_or_else(|| UnsupportedLocale(locale.to_string()))
+    }
+}
+
+// INFO: telegram sends IETF language tags (i.e. `uk`, `es-419`), some of our
+//      codes differ from the ISO 639-1 ones
+const LANGUAGE_ALIASES: &[(&str, Locale)] = &[("uk", Locale::UA)];
+
+impl Locale {
+    /// Maps a telegram `language_code` onto a supported locale: exact code first,
+    /// then aliases, then the primary language of regional variants.
+    pub fn from_language_code(language_code: &str) -> Option<Locale> {
+        let code = language_code.trim().to_lowercase().replace('_', "-");
+        let primary = code.split('-').next().unwrap_or_default();
+
+        let supported = |code: &str| {
+            code.parse::<Locale>().ok().or_else(|| {
+                LANGUAGE_ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == code)
+                    .map(|(_, locale)| locale.clone())
+            })
+        };
+
+        supported(&code).or_else(|| supported(primary))
+    }
+
+    /// Locale of a new user, `default` if telegram sent no language or an unsupported one.
+    pub fn detect(language_code: Option<&str>, default: &Locale) -> Locale {
+        language_code
+            .and_then(Locale::from_language_code)
+            .unwrap_or_else(|| default.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_roundtrip_through_display_fromstr_and_serde() {
+        for locale in Locale::ALL {
+            assert_eq!(locale.to_string().parse::<Locale>().as_ref(), Ok(locale));
+
+            let json = serde_json::to_string(locale).unwrap();
+            assert_eq!(json, format!("\"{}\"", locale.code()));
+            assert_eq!(&serde_json::from_str::<Locale>(&json).unwrap(), locale);
+        }
+    }
+
+    #[test]
+    fn unknown_code_is_rejected() {
+        assert!("xx".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn language_codes_fall_back_to_supported_locales() {
+        assert_eq!(Locale::from_language_code("es"), Some(Locale::ES));
+        assert_eq!(Locale::from_language_code("es-419"), Some(Locale::ES));
+        assert_eq!(Locale::from_language_code("IT_it"), Some(Locale::IT));
+        assert_eq!(Locale::from_language_code("uk"), Some(Locale::UA));
+        assert_eq!(Locale::from_language_code("ua"), Some(Locale::UA));
+        assert_eq!(Locale::from_language_code("pt-br"), None);
+
+        assert_eq!(Locale::detect(Some("en-GB"), &Locale::IT), Locale::EN);
+        assert_eq!(Locale::detect(Some("de"), &Locale::IT), Locale::IT);
+        assert_eq!(Locale::detect(None, &Locale::ES), Locale::ES);
+    }
+}
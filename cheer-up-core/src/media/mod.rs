@@ -0,0 +1,151 @@
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+mod local;
+mod s3;
+
+pub use local::LocalMediaStore;
+pub use s3::{S3Config, S3MediaStore};
+
+pub type Result<T, E = MediaError> = std::result::Result<T, E>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MediaError {
+    #[error("media file not found: {0}")]
+    NotFound(String),
+    #[error("invalid media key: {0:?}")]
+    InvalidKey(String),
+    #[error("media storage io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("media storage request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("media storage responded with status {status}: {body}")]
+    Status { status: StatusCode, body: String },
+}
+
+// INFO: lets bot handlers use `?` on media calls inside `ResponseResult` functions
+#[cfg(feature = "teloxide")]
+impl From<MediaError> for teloxide_core::RequestError {
+    fn from(err: MediaError) -> Self {
+        match err {
+            MediaError::Io(e) => teloxide_core::RequestError::Io(e),
+            MediaError::Request(e) => e.into(),
+            other => teloxide_core::RequestError::Io(std::io::Error::other(other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaEntry {
+    pub key: String,
+    pub size: u64,
+    /// `None` if the store does not tell
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a garbage collection pass over the media store, see `POST /api/media/gc`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GcReport {
+    /// Keys of the files deleted, no note referred to them
+    pub removed: Vec<String>,
+    pub reclaimed_bytes: u64,
+    /// Notes whose `media_key` is missing from the store
+    pub missing: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GcReportBody<T> {
+    pub gc: T,
+}
+
+/// Blob storage for video note files.
+///
+/// Keys are relative, `/` separated paths (i.e. `<telegram_id>_<username>/<file_name>`),
+/// see [`media_key`].
+#[async_trait]
+pub trait MediaStore: fmt::Debug + Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+    /// Deleting a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    async fn exists(&self, key: &str) -> Result<bool>;
+
+    /// Lists every stored file whose key starts with `prefix`.
+    async fn list(&self, prefix: &str) -> Result<Vec<MediaEntry>>;
+}
+
+/// Key under which a note uploaded by a telegram user is stored.
+pub fn media_key(telegram_id: i64, username: &str, file_name: &str) -> String {
+    format!("{}_{}/{}", telegram_id, username, file_name)
+}
+
+/// Telegram id and file name of a [`media_key`], `None` for keys in another layout.
+pub fn parse_media_key(key: &str) -> Option<(i64, &str)> {
+    let (folder, file_name) = key.split_once('/')?;
+    let (telegram_id, username) = folder.split_once('_')?;
+
+    if file_name.is_empty() || file_name.contains('/') || username.is_empty() {
+        return None;
+    }
+
+    Some((telegram_id.parse().ok()?, file_name))
+}
+
+// INFO: keys end up in filesystem paths and urls, refuse anything escaping the store root
+pub(crate) fn validate_key(key: &str) -> Result<()> {
+    let invalid = key.is_empty()
+        || key.starts_with('/')
+        || key.contains('\\')
+        || key
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..");
+
+    if invalid {
+        return Err(MediaError::InvalidKey(key.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Which [`MediaStore`] implementation to use, read from the `[media]` config section.
+#[derive(Debug, Clone)]
+pub enum MediaConfig {
+    Local { root: PathBuf },
+    S3(S3Config),
+}
+
+impl MediaConfig {
+    pub fn build(&self) -> Arc<dyn MediaStore> {
+        match self {
+            MediaConfig::Local { root } => Arc::new(LocalMediaStore::new(root)),
+            MediaConfig::S3(config) => Arc::new(S3MediaStore::new(config.clone())),
+        }
+    }
+}
+
+static GLOBAL_STORE: OnceLock<Arc<dyn MediaStore>> = OnceLock::new();
+
+/// Installs the store returned by [`global`], call it once at startup.
+/// Later calls are ignored.
+pub fn init(store: Arc<dyn MediaStore>) {
+    if GLOBAL_STORE.set(store).is_err() {
+        log::warn!("[CHEER_UP_CORE] media store already initialized, ignoring");
+    }
+}
+
+pub fn global() -> &'static Arc<dyn MediaStore> {
+    GLOBAL_STORE
+        .get()
+        .expect("media store must be initialized at startup")
+}
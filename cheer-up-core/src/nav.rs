@@ -0,0 +1,64 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+static GLOBAL_TRACKER: OnceLock<PageTracker> = OnceLock::new();
+
+/// Process-wide tracker of the bot pages.
+pub fn global() -> &'static PageTracker {
+    GLOBAL_TRACKER.get_or_init(PageTracker::default)
+}
+
+/// Latest page message of each chat, by telegram chat and message ids.
+///
+/// Bots edit a page in place when one of its buttons is tapped and send a new
+/// one for commands, the buttons left on older pages are stale. Pages are kept
+/// in memory, after a restart every page is live until its chat gets a new one.
+#[derive(Debug, Default)]
+pub struct PageTracker {
+    pages: Mutex<HashMap<i64, i32>>,
+}
+
+impl PageTracker {
+    /// Makes `message_id` the page of `chat_id`, returns the page it replaces.
+    pub fn set_page(&self, chat_id: i64, message_id: i32) -> Option<i32> {
+        self.pages
+            .lock()
+            .unwrap()
+            .insert(chat_id, message_id)
+            .filter(|previous| *previous != message_id)
+    }
+
+    /// Current page of `chat_id`, `None` if none was shown since the bot started.
+    pub fn page(&self, chat_id: i64) -> Option<i32> {
+        self.pages.lock().unwrap().get(&chat_id).copied()
+    }
+
+    /// Whether `message_id` is a page of `chat_id` left behind by a newer one.
+    pub fn is_stale(&self, chat_id: i64, message_id: i32) -> bool {
+        self.page(chat_id).is_some_and(|page| page != message_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_pages_are_stale() {
+        let tracker = PageTracker::default();
+
+        // INFO: nothing is known about pages sent before a restart
+        assert!(!tracker.is_stale(1, 10));
+
+        assert_eq!(tracker.set_page(1, 10), None);
+        assert_eq!(tracker.set_page(1, 10), None);
+        assert!(!tracker.is_stale(1, 10));
+
+        assert_eq!(tracker.set_page(1, 12), Some(10));
+        assert!(tracker.is_stale(1, 10));
+        assert!(!tracker.is_stale(1, 12));
+        assert!(!tracker.is_stale(2, 10));
+    }
+}
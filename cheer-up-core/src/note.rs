@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Note {
+    pub id: i64,
+    pub user_id: i64,
+    pub file_name: String,
+    // INFO: telegram metadata, missing for notes uploaded before file ids were recorded
+    #[serde(default)]
+    pub file_id: Option<String>,
+    #[serde(default)]
+    pub file_unique_id: Option<String>,
+    #[serde(default)]
+    pub duration: Option<i64>,
+    #[serde(default)]
+    pub file_size: Option<i64>,
+    // INFO: key inside the media store, missing for notes only known by `file_id`
+    #[serde(default)]
+    pub media_key: Option<String>,
+    // INFO: `None` shares the note with every group of its author
+    #[serde(default)]
+    pub group_id: Option<i64>,
+    // INFO: `None` is a note for everyone, otherwise only this user receives it
+    #[serde(default)]
+    pub recipient_id: Option<i64>,
+    // INFO: plain text shown next to the note, up to `MAX_NOTE_CAPTION_LEN` chars
+    #[serde(default)]
+    pub caption: Option<String>,
+    // INFO: UTC `YYYY-MM-DD HH:MM:SS.SSS`, missing for notes uploaded before it was recorded
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateNote {
+    pub id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewNote {
+    pub user_id: i64,
+    pub file_name: String,
+    #[serde(default)]
+    pub file_id: Option<String>,
+    #[serde(default)]
+    pub file_unique_id: Option<String>,
+    #[serde(default)]
+    pub duration: Option<i64>,
+    #[serde(default)]
+    pub file_size: Option<i64>,
+    // INFO: key inside the media store, missing for notes only known by `file_id`
+    #[serde(default)]
+    pub media_key: Option<String>,
+    // INFO: `None` shares the note with every group of its author
+    #[serde(default)]
+    pub group_id: Option<i64>,
+    // INFO: `None` is a note for everyone, otherwise only this user receives it
+    #[serde(default)]
+    pub recipient_id: Option<i64>,
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+/// Longest caption accepted by `POST /api/notes`, in chars.
+pub const MAX_NOTE_CAPTION_LEN: usize = 200;
+
+/// Largest `limit` accepted by `GET /api/notes`.
+pub const MAX_NOTE_PAGE_SIZE: i64 = 100;
+
+/// Query string of `GET /api/notes`, oldest notes first.
+///
+/// Every matching note is returned unless `limit` is set, then pass the
+/// `next_cursor` or `prev_cursor` of the returned [`NotePage`] as `after` or
+/// `before` to move to the next or previous page.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoteListQuery {
+    /// Only notes shared with this user's groups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub for_user: Option<i64>,
+    /// Only notes addressed to `for_user`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub personal: bool,
+    /// Only notes uploaded by this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<i64>,
+    /// Only notes addressed to this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<i64>,
+    /// Only notes uploaded at or after this UTC time, `YYYY-MM-DD[ HH:MM[:SS[.SSS]]]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Only notes uploaded before this UTC time, `YYYY-MM-DD[ HH:MM[:SS[.SSS]]]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// Only notes with a greater id, the page starts right after it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<i64>,
+    /// Only notes with a smaller id, the page ends right before it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<i64>,
+    /// Page size, up to [`MAX_NOTE_PAGE_SIZE`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
+/// Body of `GET /api/notes`, a superset of [`NoteListBody`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotePage {
+    pub notes: Vec<Note>,
+    /// Notes matching the filters, on every page
+    pub total: i64,
+    /// Matching notes before this page
+    pub offset: i64,
+    /// `after` of the next page, `None` on the last one
+    pub next_cursor: Option<i64>,
+    /// `before` of the previous page, `None` on the first one
+    pub prev_cursor: Option<i64>,
+}
+
+impl NotePage {
+    /// 1-based number of this page and the page count, for pages of `page_size` notes.
+    pub fn position(&self, page_size: i64) -> (i64, i64) {
+        let page_size = page_size.max(1);
+        let page = (self.offset + page_size - 1) / page_size + 1;
+
+        // INFO: pages are cut by id, after deletions they may not line up with page size
+        let pages = ((self.total + page_size - 1) / page_size)
+            .max(page + self.next_cursor.is_some() as i64);
+
+        (page, pages)
+    }
+}
+
+/// Query string of `DELETE /api/notes/:note_id` and `DELETE /api/notes/user/:user_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeleteNoteQuery {
+    /// User the deletion is made for, must own the notes or be an admin
+    pub acting_user: i64,
+}
+
+/// Body of `DELETE /api/notes`, the notes are deleted all together or not at all.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeleteNotes {
+    pub note_ids: Vec<i64>,
+    /// User the deletion is made for, must own every note or be an admin
+    pub acting_user: i64,
+}
+
+/// How `GET /api/notes/random` picks a note among the ones a user can receive.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+pub enum SelectionStrategy {
+    /// Never seen notes first, then the least recently seen one, so every note
+    /// is served once before any repeats
+    #[default]
+    ShuffleBag,
+    /// Every note equally likely
+    Uniform,
+    /// Recently uploaded notes are more likely
+    Recency,
+    /// Every author equally likely, regardless of how many notes they uploaded
+    AuthorFairness,
+    /// Notes saved to favorites by more users are more likely
+    Favorites,
+    /// Notes uploaded around the current time of day are more likely
+    TimeOfDay,
+}
+
+/// Query string of `GET /api/notes/random`.
+///
+/// Views are recorded separately with `POST /api/notes/:note_id/views`, they drive
+/// [`SelectionStrategy::ShuffleBag`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RandomNoteQuery {
+    /// Pick among notes shared with this user's groups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub for_user: Option<i64>,
+    /// Only notes addressed to `for_user`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub personal: bool,
+    /// Overrides the strategy of `for_user` and the deployment default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<SelectionStrategy>,
+    /// Makes the pick reproducible, meant for tests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// Body of `GET`/`PATCH /api/strategy/:user_id`, `None` follows the deployment default.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrategyBody<T> {
+    pub strategy: T,
+}
+
+/// Body of `PUT /api/notes/:note_id/recipient`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteRecipient {
+    /// Must share a group with the note author, `None` makes the note public again
+    #[serde(default)]
+    pub recipient_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct NoteView {
+    pub id: i64,
+    pub note_id: i64,
+    pub user_id: i64,
+    /// UTC, `YYYY-MM-DD HH:MM:SS.SSS`
+    pub viewed_at: String,
+}
+
+/// Body of `POST /api/notes/:note_id/views`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewNoteView {
+    pub user_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NoteViewBody<T> {
+    pub view: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NoteBody<T> {
+    pub note: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NoteListBody<T> {
+    pub notes: Vec<T>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_position() {
+        let page = |total, offset, next_cursor| NotePage {
+            total,
+            offset,
+            next_cursor,
+            ..Default::default()
+        };
+
+        assert_eq!(page(0, 0, None).position(5), (1, 1));
+        assert_eq!(page(12, 0, Some(7)).position(5), (1, 3));
+        assert_eq!(page(12, 10, None).position(5), (3, 3));
+        // INFO: a page cut after a deletion still has a next page to go to
+        assert_eq!(page(10, 7, Some(30)).position(5), (3, 4));
+    }
+}
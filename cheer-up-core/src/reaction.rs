@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+pub enum Reaction {
+    Heart,
+    Laugh,
+    Cry,
+}
+
+impl Reaction {
+    pub const ALL: &'static [Reaction] = &[Reaction::Heart, Reaction::Laugh, Reaction::Cry];
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Reaction::Heart => "❤️",
+            Reaction::Laugh => "😂",
+            Reaction::Cry => "😢",
+        }
+    }
+}
+
+/// Body of `POST /api/notes/:note_id/reactions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewReaction {
+    pub user_id: i64,
+    pub reaction: Reaction,
+}
+
+/// How friends reacted to a note.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct NoteReactions {
+    pub note_id: i64,
+    pub heart: i64,
+    pub laugh: i64,
+    pub cry: i64,
+    /// Users having the note in their favorites
+    pub favorites: i64,
+}
+
+impl NoteReactions {
+    pub fn count(&self, reaction: Reaction) -> i64 {
+        match reaction {
+            Reaction::Heart => self.heart,
+            Reaction::Laugh => self.laugh,
+            Reaction::Cry => self.cry,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heart + self.laugh + self.cry + self.favorites == 0
+    }
+}
+
+/// Body of `POST /api/users/:user_id/favorites`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewFavorite {
+    pub note_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReactionsBody<T> {
+    pub reactions: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReactionListBody<T> {
+    pub reactions: Vec<T>,
+}
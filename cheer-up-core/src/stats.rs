@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stats {
+    pub total_videonotes: i32,
+    pub users: Vec<UserStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct UserStats {
+    pub username: String,
+    pub videonotes: i64,
+}
+
+/// Query string of `GET /api/stats`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsQuery {
+    /// Only count users and notes in this user's groups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub for_user: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsBody<T> {
+    pub stats: T,
+}
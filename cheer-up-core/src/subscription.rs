@@ -0,0 +1,278 @@
+use chrono::{
+    DateTime, Datelike, Days, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// Format of delivery times, always UTC so that sqlite can compare them as text.
+pub const DELIVERY_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// Format of the local delivery time typed by users, i.e. `09:00`.
+pub const LOCAL_TIME_FORMAT: &str = "%H:%M";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+pub enum Frequency {
+    #[default]
+    Daily,
+    /// Once a week, on the weekday the schedule was set
+    Weekly,
+}
+
+/// A user receiving a random note automatically.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Subscription {
+    pub user_id: i64,
+    pub telegram_id: i64,
+    pub frequency: Frequency,
+    /// Local delivery time, formatted with [`LOCAL_TIME_FORMAT`]
+    pub local_time: String,
+    /// IANA timezone name, i.e. `Europe/Kyiv`
+    pub timezone: String,
+    /// Delivery day of weekly subscriptions, 0 is monday
+    pub weekday: Option<i64>,
+    /// Formatted with [`DELIVERY_TIME_FORMAT`]
+    pub next_delivery_at: String,
+    pub last_delivered_at: Option<String>,
+}
+
+/// Body of `PUT /api/subscriptions/:user_id`, replaces any previous schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSubscription {
+    pub frequency: Frequency,
+    pub local_time: String,
+    pub timezone: String,
+    pub weekday: Option<i64>,
+    pub next_delivery_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscriptionBody<T> {
+    pub subscription: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscriptionListBody<T> {
+    pub subscriptions: Vec<T>,
+}
+
+/// Body of `POST /api/subscriptions/:user_id/delivered`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionDelivered {
+    pub next_delivery_at: String,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    #[error("expected `[daily|weekly] HH:MM TIMEZONE`")]
+    Format,
+    #[error("invalid time {0:?}, expected HH:MM")]
+    Time(String),
+    #[error("unknown timezone {0:?}")]
+    Timezone(String),
+    #[error("invalid weekday {0}")]
+    Weekday(i64),
+}
+
+/// When a subscription delivers, in the user timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    pub frequency: Frequency,
+    pub time: NaiveTime,
+    pub timezone: Tz,
+    /// Only set for [`Frequency::Weekly`]
+    pub weekday: Option<Weekday>,
+}
+
+impl Schedule {
+    /// Parses `[daily|weekly] HH:MM TIMEZONE`, i.e. `09:00 Europe/Kyiv`.
+    /// Weekly schedules deliver on the weekday of `now` in their timezone.
+    pub fn parse(input: &str, now: DateTime<Utc>) -> Result<Schedule, ScheduleError> {
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+
+        let (frequency, time, timezone) = match tokens.as_slice() {
+            [time, timezone] => (Frequency::Daily, *time, *timezone),
+            [frequency, time, timezone] => match frequency.to_lowercase().as_str() {
+                "daily" => (Frequency::Daily, *time, *timezone),
+                "weekly" => (Frequency::Weekly, *time, *timezone),
+                _ => return Err(ScheduleError::Format),
+            },
+            _ => return Err(ScheduleError::Format),
+        };
+
+        let time = NaiveTime::parse_from_str(time, LOCAL_TIME_FORMAT)
+            .map_err(|_| ScheduleError::Time(time.to_string()))?;
+        let timezone = timezone
+            .parse::<Tz>()
+            .map_err(|_| ScheduleError::Timezone(timezone.to_string()))?;
+
+        let weekday = match frequency {
+            Frequency::Daily => None,
+            Frequency::Weekly => Some(now.with_timezone(&timezone).weekday()),
+        };
+
+        Ok(Schedule {
+            frequency,
+            time,
+            timezone,
+            weekday,
+        })
+    }
+
+    pub fn from_subscription(subscription: &Subscription) -> Result<Schedule, ScheduleError> {
+        let time = NaiveTime::parse_from_str(&subscription.local_time, LOCAL_TIME_FORMAT)
+            .map_err(|_| ScheduleError::Time(subscription.local_time.clone()))?;
+        let timezone = subscription
+            .timezone
+            .parse::<Tz>()
+            .map_err(|_| ScheduleError::Timezone(subscription.timezone.clone()))?;
+        let weekday = match subscription.weekday {
+            Some(day) => Some(
+                u8::try_from(day)
+                    .ok()
+                    .and_then(|day| Weekday::try_from(day).ok())
+                    .ok_or(ScheduleError::Weekday(day))?,
+            ),
+            None => None,
+        };
+
+        Ok(Schedule {
+            frequency: subscription.frequency,
+            time,
+            timezone,
+            weekday,
+        })
+    }
+
+    /// First delivery strictly after `after`. Missed deliveries are not
+    /// replayed one by one, the schedule simply moves to the next one.
+    pub fn next_delivery(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        let today = after.with_timezone(&self.timezone).date_naive();
+
+        // INFO: a weekly delivery is at most 7 days after today's, skipped or not
+        (0..=7)
+            .filter_map(|days| today.checked_add_days(Days::new(days)))
+            .filter(|date| self.weekday.is_none_or(|weekday| date.weekday() == weekday))
+            .filter_map(|date| self.resolve(date.and_time(self.time)))
+            .find(|at| *at > after)
+            .unwrap_or(after + Days::new(7))
+    }
+
+    pub fn to_new_subscription(&self, now: DateTime<Utc>) -> NewSubscription {
+        NewSubscription {
+            frequency: self.frequency,
+            local_time: self.time.format(LOCAL_TIME_FORMAT).to_string(),
+            timezone: self.timezone.name().to_string(),
+            weekday: self
+                .weekday
+                .map(|weekday| weekday.num_days_from_monday() as i64),
+            next_delivery_at: format_delivery_time(self.next_delivery(now)),
+        }
+    }
+
+    // INFO: times skipped by a DST change are delivered an hour later,
+    //       repeated ones at their first occurrence
+    fn resolve(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                let later = local.checked_add_signed(TimeDelta::try_hours(1)?)?;
+                self.timezone.from_local_datetime(&later).earliest()
+            })
+            .map(|at| at.with_timezone(&Utc))
+    }
+}
+
+pub fn format_delivery_time(at: DateTime<Utc>) -> String {
+    at.format(DELIVERY_TIME_FORMAT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(at: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(at, DELIVERY_TIME_FORMAT)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn parses_daily_and_weekly_schedules() {
+        // INFO: a wednesday, already thursday in Tokyo
+        let now = utc("2024-03-06 20:00:00");
+
+        let daily = Schedule::parse("09:00 Europe/Kyiv", now).unwrap();
+        assert_eq!(daily.frequency, Frequency::Daily);
+        assert_eq!(daily.timezone, chrono_tz::Europe::Kyiv);
+        assert_eq!(daily.weekday, None);
+
+        let weekly = Schedule::parse("WEEKLY 07:30 Asia/Tokyo", now).unwrap();
+        assert_eq!(weekly.frequency, Frequency::Weekly);
+        assert_eq!(weekly.weekday, Some(Weekday::Thu));
+
+        assert_eq!(
+            Schedule::parse("9am Europe/Kyiv", now),
+            Err(ScheduleError::Time("9am".to_string()))
+        );
+        assert_eq!(
+            Schedule::parse("09:00 Mars/Olympus", now),
+            Err(ScheduleError::Timezone("Mars/Olympus".to_string()))
+        );
+        assert_eq!(Schedule::parse("09:00", now), Err(ScheduleError::Format));
+    }
+
+    #[test]
+    fn next_delivery_follows_local_time() {
+        let schedule = Schedule::parse("09:00 Europe/Kyiv", utc("2024-01-10 00:00:00")).unwrap();
+
+        // INFO: Kyiv is UTC+2 in winter
+        assert_eq!(
+            schedule.next_delivery(utc("2024-01-10 06:59:59")),
+            utc("2024-01-10 07:00:00")
+        );
+        assert_eq!(
+            schedule.next_delivery(utc("2024-01-10 07:00:00")),
+            utc("2024-01-11 07:00:00")
+        );
+
+        // INFO: missed for days, the next delivery is still in the future
+        assert_eq!(
+            schedule.next_delivery(utc("2024-01-20 12:00:00")),
+            utc("2024-01-21 07:00:00")
+        );
+    }
+
+    #[test]
+    fn next_delivery_handles_dst_and_weekdays() {
+        // INFO: 03:30 does not exist in Kyiv on 2024-03-31, clocks jump from 03:00 to 04:00
+        let schedule = Schedule::parse("03:30 Europe/Kyiv", utc("2024-03-30 00:00:00")).unwrap();
+        assert_eq!(
+            schedule.next_delivery(utc("2024-03-30 12:00:00")),
+            utc("2024-03-31 01:30:00")
+        );
+
+        // INFO: set on a wednesday, delivered every wednesday
+        let schedule =
+            Schedule::parse("weekly 09:00 Europe/Kyiv", utc("2024-01-10 12:00:00")).unwrap();
+        assert_eq!(
+            schedule.next_delivery(utc("2024-01-10 12:00:00")),
+            utc("2024-01-17 07:00:00")
+        );
+
+        let subscription = Subscription {
+            user_id: 1,
+            telegram_id: 1,
+            frequency: Frequency::Weekly,
+            local_time: "09:00".to_string(),
+            timezone: "Europe/Kyiv".to_string(),
+            weekday: Some(2),
+            next_delivery_at: "2024-01-17 07:00:00".to_string(),
+            last_delivered_at: None,
+        };
+        assert_eq!(Schedule::from_subscription(&subscription), Ok(schedule));
+    }
+}
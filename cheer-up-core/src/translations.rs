@@ -0,0 +1,166 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+
+use crate::locale::Locale;
+
+/// Locale every translation is compared to.
+pub const REFERENCE_LOCALE: Locale = Locale::EN;
+
+/// Translation problem found by [`check_translations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslationIssue {
+    /// A supported [`Locale`] has no file in the locales directory
+    MissingLocale(Locale),
+    /// A key passed to `t!` is not translated in `locale`
+    MissingKey { locale: Locale, key: String },
+    /// `locale` uses other `%{placeholders}` than [`REFERENCE_LOCALE`]
+    PlaceholderMismatch {
+        locale: Locale,
+        key: String,
+        expected: BTreeSet<String>,
+        found: BTreeSet<String>,
+    },
+}
+
+impl fmt::Display for TranslationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingLocale(locale) => write!(f, "{}: locale file is missing", locale),
+            Self::MissingKey { locale, key } => write!(f, "{}: `{}` is missing", locale, key),
+            Self::PlaceholderMismatch {
+                locale,
+                key,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: `{}` has placeholders {:?}, expected {:?}",
+                locale, key, found, expected
+            ),
+        }
+    }
+}
+
+/// Checks the `rust-i18n` files in `locales_dir` against the `t!` calls of every `.rs`
+/// file under `sources_dir`: every [`Locale`] must translate every key used, with the
+/// same placeholders as [`REFERENCE_LOCALE`]. Meant to be run by the bots tests.
+pub fn check_translations(
+    locales_dir: &Path,
+    sources_dir: &Path,
+) -> std::io::Result<Vec<TranslationIssue>> {
+    let translations = rust_i18n_support::load_locales(&locales_dir.to_string_lossy(), |_| false);
+
+    let mut used_keys = BTreeSet::new();
+    for source in rust_sources(sources_dir)? {
+        used_keys.extend(translation_keys(&fs::read_to_string(source)?));
+    }
+
+    let mut issues = vec![];
+    let empty = Default::default();
+    let reference = translations.get(REFERENCE_LOCALE.code()).unwrap_or(&empty);
+
+    for locale in Locale::ALL {
+        let Some(translated) = translations.get(locale.code()) else {
+            issues.push(TranslationIssue::MissingLocale(locale.clone()));
+            continue;
+        };
+
+        for key in &used_keys {
+            if !translated.contains_key(key) {
+                issues.push(TranslationIssue::MissingKey {
+                    locale: locale.clone(),
+                    key: key.clone(),
+                });
+            }
+        }
+
+        // INFO: sorted so that failures read the same on every run
+        let translated: BTreeMap<_, _> = translated.iter().collect();
+        for (key, text) in translated {
+            let Some(reference_text) = reference.get(key) else {
+                continue;
+            };
+
+            let expected = placeholders(reference_text);
+            let found = placeholders(text);
+            if expected != found {
+                issues.push(TranslationIssue::PlaceholderMismatch {
+                    locale: locale.clone(),
+                    key: key.clone(),
+                    expected,
+                    found,
+                });
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Every `.rs` file under `dir`, nested modules included.
+fn rust_sources(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut sources = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            sources.extend(rust_sources(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            sources.push(path);
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Literal keys passed to `t!`, calls in line comments are skipped.
+fn translation_keys(source: &str) -> BTreeSet<String> {
+    let call = Regex::new(r#"\bt!\(\s*"([^"]+)""#).unwrap();
+    let code: Vec<&str> = source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect();
+
+    call.captures_iter(&code.join("\n"))
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+fn placeholders(text: &str) -> BTreeSet<String> {
+    let placeholder = Regex::new(r"%\{(\w+)\}").unwrap();
+
+    placeholder
+        .captures_iter(text)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_keys_and_placeholders() {
+        let source = r#"
+            format!("{}", t!("start_page", locale = locale, user = user));
+            &t!(
+                "buttons.go_home",
+                locale = locale.to_string().as_str()
+            );
+            // t!("commented_out_page", locale = locale)
+        "#;
+        assert_eq!(
+            translation_keys(source),
+            BTreeSet::from(["buttons.go_home".to_string(), "start_page".to_string()])
+        );
+
+        assert_eq!(
+            placeholders("<b>Hi %{user}!</b> %{total_notes} notes, %{user} 😊️"),
+            BTreeSet::from(["total_notes".to_string(), "user".to_string()])
+        );
+    }
+}
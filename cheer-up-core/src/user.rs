@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Locale;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct User {
+    pub id: i64,
+    pub telegram_id: i64,
+    pub username: String,
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub locale: Locale,
+    #[serde(default)]
+    pub role: Role,
+}
+
+impl User {
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+}
+
+/// What a user is allowed to do besides managing their own notes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+pub enum Role {
+    #[default]
+    User,
+    /// Can delete the notes of anyone, granted straight in the database
+    Admin,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateUser {
+    pub id: Option<i64>,
+    pub telegram_id: Option<i64>,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub locale: Option<Locale>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct NewUser {
+    pub telegram_id: i64,
+    pub username: String,
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub locale: Locale,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserBody<T> {
+    pub user: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserListBody<T> {
+    pub users: Vec<T>,
+}
@@ -1,12 +1,19 @@
+use cheer_up_core::callback;
+use log::error;
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup};
 
 use crate::{
     callbacks::{Payload, QueryData, Topic},
     locale::Locale,
+    videonotes::Note,
 };
 
 pub fn make_button(label: &str, query_data: &QueryData) -> InlineKeyboardButton {
-    let data = serde_json::to_string(query_data).unwrap_or("none".to_string());
+    // INFO: signed and at most 64 bytes, long payloads are kept in memory by the codec
+    let data = callback::global().encode(query_data).unwrap_or_else(|e| {
+        error!("[MAKE_BUTTON] cannot encode {:?}: {}", query_data, e);
+        "none".to_string()
+    });
 
     InlineKeyboardButton::new(label, InlineKeyboardButtonKind::CallbackData(data))
 }
@@ -23,6 +30,55 @@ pub fn list_all_notes_button(payload: Option<Payload>, locale: &Locale) -> Inlin
     )
 }
 
+pub fn show_note_button(vnote: &Note, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::ShowNote,
+        payload: Some(Payload::NoteId(vnote.id)),
+    };
+
+    let label = t!(
+        "buttons.show_note",
+        locale = locale.to_string().as_str(),
+        note_id = vnote.id
+    );
+
+    // INFO: upload day, `created_at` starts with `YYYY-MM-DD`
+    let label = match vnote.created_at.as_deref().and_then(|date| date.get(..10)) {
+        Some(day) => format!("{} · {}", label, day),
+        None => label.to_string(),
+    };
+
+    make_button(&label, &query_data)
+}
+
+/// `topic` is the list the button pages through, `/list` or `/moderate`.
+pub fn prev_page_button(
+    topic: Topic,
+    payload: Option<Payload>,
+    locale: &Locale,
+) -> InlineKeyboardButton {
+    let query_data = QueryData { topic, payload };
+
+    make_button(
+        &t!("buttons.prev_page", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
+/// `topic` is the list the button pages through, `/list` or `/moderate`.
+pub fn next_page_button(
+    topic: Topic,
+    payload: Option<Payload>,
+    locale: &Locale,
+) -> InlineKeyboardButton {
+    let query_data = QueryData { topic, payload };
+
+    make_button(
+        &t!("buttons.next_page", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
 pub fn erase_all_notes_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
     let query_data = QueryData {
         topic: Topic::EraseAllNotes,
@@ -199,3 +255,142 @@ pub fn set_language_IT_button(payload: Option<Payload>, locale: &Locale) -> Inli
         &query_data,
     )
 }
+
+pub fn set_digest_muted_button(muted: bool, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::SetDigestMuted,
+        payload: Some(Payload::Muted(muted)),
+    };
+
+    let label = match muted {
+        true => t!("buttons.mute_digest", locale = locale.to_string().as_str()),
+        false => t!(
+            "buttons.unmute_digest",
+            locale = locale.to_string().as_str()
+        ),
+    };
+
+    make_button(&label, &query_data)
+}
+
+// INFO: labelled with the recipient username, no translation needed
+pub fn pick_recipient_button(label: &str, payload: Option<Payload>) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::PickRecipient,
+        payload,
+    };
+
+    make_button(label, &query_data)
+}
+
+// INFO: a note picked for no friend in particular is for everyone
+pub fn everyone_button(locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::PickRecipient,
+        payload: None,
+    };
+
+    make_button(
+        &t!("buttons.everyone", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
+pub fn skip_caption_button(locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::SkipCaption,
+        payload: None,
+    };
+
+    make_button(
+        &t!("buttons.skip_caption", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
+pub fn confirm_note_button(locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::ConfirmNote,
+        payload: None,
+    };
+
+    make_button(
+        &t!("buttons.confirm", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
+pub fn cancel_dialogue_button(locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::CancelDialogue,
+        payload: None,
+    };
+
+    make_button(
+        &t!("buttons.cancel", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
+/// `payload` is the cursor of the `/list` page to select notes on.
+pub fn select_notes_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::SelectNotes,
+        payload,
+    };
+
+    make_button(
+        &t!("buttons.select_notes", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
+pub fn toggle_note_button(vnote: &Note, selected: bool, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::ToggleNote,
+        payload: Some(Payload::NoteId(vnote.id)),
+    };
+
+    let label = match selected {
+        true => t!(
+            "buttons.toggle_note.selected",
+            locale = locale.to_string().as_str(),
+            note_id = vnote.id
+        ),
+        false => t!(
+            "buttons.toggle_note.unselected",
+            locale = locale.to_string().as_str(),
+            note_id = vnote.id
+        ),
+    };
+
+    make_button(&label, &query_data)
+}
+
+pub fn delete_selected_notes_button(count: usize, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::DeleteSelectedNotes,
+        payload: None,
+    };
+
+    make_button(
+        &t!(
+            "buttons.delete_selected_notes",
+            locale = locale.to_string().as_str(),
+            count = count
+        ),
+        &query_data,
+    )
+}
+
+pub fn confirm_bulk_delete_button(locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::ConfirmBulkDelete,
+        payload: None,
+    };
+
+    make_button(
+        &t!("buttons.confirm", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
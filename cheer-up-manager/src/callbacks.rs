@@ -6,10 +6,15 @@ use teloxide::{
     Bot, RequestError,
 };
 
+use cheer_up_core::callback::{self, CallbackError};
 use serde::{self, Deserialize, Serialize};
-use serde_json;
 
-use crate::{commands, locale::Locale, user::UserId};
+use crate::{
+    commands,
+    dialogues::{exit_dialogue, ManagerDialogue, State},
+    locale::Locale,
+    nav,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct QueryData {
@@ -23,6 +28,13 @@ pub enum Payload {
     Username(String),
     UserId(i64),
     NoteId(i64),
+    // INFO: (note id, recipient user id), carried by retired buttons only
+    NoteRecipient(i64, i64),
+    // INFO: digest mute setting to apply
+    Muted(bool),
+    // INFO: cursors of the `/list` or `/moderate` page to show, see `NotePage`
+    PageAfter(i64),
+    PageBefore(i64),
 }
 
 impl Payload {
@@ -50,10 +62,15 @@ impl fmt::Display for Payload {
             Payload::Username(text) => write!(f, "{}", text),
             Payload::UserId(id) => write!(f, "{}", id),
             Payload::NoteId(id) => write!(f, "{}", id),
+            Payload::NoteRecipient(note_id, user_id) => write!(f, "{}:{}", note_id, user_id),
+            Payload::Muted(muted) => write!(f, "{}", muted),
+            Payload::PageAfter(id) => write!(f, ">{}", id),
+            Payload::PageBefore(id) => write!(f, "<{}", id),
         }
     }
 }
 
+// INFO: buttons carry the variant index, new topics go last so sent buttons keep theirs
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum Topic {
     ListAllNotes,
@@ -67,12 +84,28 @@ pub enum Topic {
     GoLanguagePage,
     GoHelpPage,
     SetLanguage,
+    // INFO: retired, still listed so that the topics after them keep their index
+    ChooseRecipient,
+    SetRecipient,
+    SetDigestMuted,
+    ModerateNotes,
+    ShowNote,
+    PickRecipient,
+    SkipCaption,
+    ConfirmNote,
+    CancelDialogue,
+    SelectNotes,
+    ToggleNote,
+    DeleteSelectedNotes,
+    ConfirmBulkDelete,
 }
 
 impl Topic {
     pub fn name(&self) -> String {
         match self {
             Topic::ListAllNotes => "#list".to_string(),
+            Topic::ModerateNotes => "#moderate".to_string(),
+            Topic::ShowNote => "#show_note".to_string(),
             Topic::EraseAllNotes => "#erase_all".to_string(),
             Topic::ConfirmEraseAllNotes => "#confirm_erase_all".to_string(),
             Topic::DeleteNote => "#delete".to_string(),
@@ -83,19 +116,33 @@ impl Topic {
             Topic::GoLanguagePage => "#language".to_string(),
             Topic::GoHelpPage => "#help".to_string(),
             Topic::SetLanguage => "#set_language".to_string(),
+            Topic::ChooseRecipient => "#choose_recipient".to_string(),
+            Topic::SetRecipient => "#set_recipient".to_string(),
+            Topic::SetDigestMuted => "#set_digest_muted".to_string(),
+            Topic::PickRecipient => "#pick_recipient".to_string(),
+            Topic::SkipCaption => "#skip_caption".to_string(),
+            Topic::ConfirmNote => "#confirm_note".to_string(),
+            Topic::CancelDialogue => "#cancel".to_string(),
+            Topic::SelectNotes => "#select_notes".to_string(),
+            Topic::ToggleNote => "#toggle_note".to_string(),
+            Topic::DeleteSelectedNotes => "#delete_selected".to_string(),
+            Topic::ConfirmBulkDelete => "#confirm_bulk_delete".to_string(),
         }
     }
 }
 
-pub async fn handle_callback(bot: Bot, query: CallbackQuery) -> Result<(), RequestError> {
-    // suppress waiting spinner from button after user taps it
-    bot.answer_callback_query(query.id).await?;
+pub async fn handle_callback(
+    bot: Bot,
+    dialogue: ManagerDialogue,
+    state: State,
+    query: CallbackQuery,
+) -> Result<(), RequestError> {
+    let query_id = query.id.clone();
 
-    let message = query.message.clone();
-    let chat = match query.message {
-        Some(msg) => Some(msg.chat),
-        None => None,
-    };
+    // INFO: only the latest page of a chat is live, buttons left on older ones are stale
+    if let Some(msg) = query.message.clone().filter(nav::is_stale) {
+        return commands::stale_button_command(&bot, msg, query_id).await;
+    }
 
     let raw_data = match query.data {
         Some(data) => data,
@@ -103,41 +150,123 @@ pub async fn handle_callback(bot: Bot, query: CallbackQuery) -> Result<(), Reque
     };
     // println!("callback query data is: {:#?}", raw_data);
 
-    let callback_query_data = serde_json::from_str::<QueryData>(&raw_data);
-    // println!("callback query data is: {:#?}", callback_query_data);
+    let callback_query_data = callback::global().decode::<QueryData>(&raw_data);
+
+    // INFO: buttons of another step of the flow, i.e. tapped twice, get an alert
+    if let (Some(msg), Ok(data)) = (query.message.clone(), &callback_query_data) {
+        if !state.accepts(data) {
+            info!(
+                "[HANDLE_CALLBACK] {} out of order in {:?}",
+                data.topic.name(),
+                state
+            );
+            return commands::out_of_order_button_command(&bot, msg, query_id).await;
+        }
+    }
+
+    let handled = dispatch_callback(
+        &bot,
+        &dialogue,
+        state,
+        query.message,
+        &raw_data,
+        callback_query_data,
+    )
+    .await;
+
+    // INFO: answered once the page is shown, the button spinner is the loading indicator
+    if let Err(e) = bot.answer_callback_query(query_id).await {
+        warn!("[HANDLE_CALLBACK] cannot answer callback query: {}", e);
+    }
+
+    handled
+}
+
+async fn dispatch_callback(
+    bot: &Bot,
+    dialogue: &ManagerDialogue,
+    state: State,
+    message: Option<Message>,
+    raw_data: &str,
+    callback_query_data: Result<QueryData, CallbackError>,
+) -> ResponseResult<()> {
+    let chat = message.as_ref().map(|msg| msg.chat.clone());
 
     match callback_query_data {
         Ok(data) => {
+            // INFO: buttons of other pages leave the current flow
+            if !data.topic.is_dialogue_step() {
+                exit_dialogue(dialogue, &state).await?;
+            }
+
             let topic = data.topic;
 
             // INFO: call handlers based on query topic passing optional payload
             match topic {
-                Topic::DeleteNote => handle_delete_note(&bot, message, chat, data.payload).await?,
+                Topic::DeleteNote => {
+                    handle_delete_note(bot, message, chat, data.payload, dialogue).await?
+                }
                 Topic::ConfirmDelete => {
-                    handle_confirm_delete(&bot, message, chat, data.payload).await?
+                    handle_confirm_delete(bot, message, chat, dialogue, state).await?
                 }
                 Topic::ListAllNotes => {
-                    handle_list_all_notes(&bot, message, chat, data.payload).await?
+                    handle_list_all_notes(bot, message, chat, data.payload).await?
+                }
+                Topic::ModerateNotes => {
+                    handle_moderate_notes(bot, message, chat, data.payload).await?
                 }
+                Topic::ShowNote => handle_show_note(bot, message, chat, data.payload).await?,
                 Topic::EraseAllNotes => {
-                    handle_erase_all_notes(&bot, message, chat, data.payload).await?
+                    handle_erase_all_notes(bot, message, chat, data.payload, dialogue).await?
                 }
                 Topic::ConfirmEraseAllNotes => {
-                    handle_confirm_erase_all_notes(&bot, message, chat, data.payload).await?
+                    handle_confirm_erase_all_notes(bot, message, chat, data.payload, dialogue)
+                        .await?
                 }
-                Topic::GoHomePage => handle_go_home_page(&bot, message, chat, data.payload).await?,
+                Topic::GoHomePage => handle_go_home_page(bot, message, chat, data.payload).await?,
                 Topic::GoUploadPage => {
-                    handle_go_upload_page(&bot, message, chat, data.payload).await?
+                    handle_go_upload_page(bot, message, chat, data.payload).await?
                 }
                 Topic::GoCreditsPage => {
-                    handle_go_credits_page(&bot, message, chat, data.payload).await?
+                    handle_go_credits_page(bot, message, chat, data.payload).await?
                 }
                 Topic::GoLanguagePage => {
-                    handle_go_language_page(&bot, message, chat, data.payload).await?
+                    handle_go_language_page(bot, message, chat, data.payload).await?
+                }
+                Topic::GoHelpPage => handle_go_help_page(bot, message, chat, data.payload).await?,
+                Topic::SetLanguage => handle_set_language(bot, message, chat, data.payload).await?,
+                // INFO: retired, recipients are picked in the upload dialogue
+                Topic::ChooseRecipient | Topic::SetRecipient => {
+                    if let Some(msg) = message {
+                        commands::invalid_button_command(bot, msg).await?
+                    }
+                }
+                Topic::SetDigestMuted => {
+                    handle_set_digest_muted(bot, message, chat, data.payload).await?
+                }
+                Topic::PickRecipient => {
+                    handle_pick_recipient(bot, message, chat, data.payload, dialogue, state).await?
+                }
+                Topic::SkipCaption => {
+                    handle_skip_caption(bot, message, chat, dialogue, state).await?
+                }
+                Topic::ConfirmNote => {
+                    handle_confirm_note(bot, message, chat, dialogue, state).await?
+                }
+                Topic::CancelDialogue => {
+                    handle_cancel_dialogue(bot, message, chat, dialogue, state).await?
                 }
-                Topic::GoHelpPage => handle_go_help_page(&bot, message, chat, data.payload).await?,
-                Topic::SetLanguage => {
-                    handle_set_language(&bot, message, chat, data.payload).await?
+                Topic::SelectNotes => {
+                    handle_select_notes(bot, message, chat, data.payload, dialogue, state).await?
+                }
+                Topic::ToggleNote => {
+                    handle_toggle_note(bot, message, chat, data.payload, dialogue, state).await?
+                }
+                Topic::DeleteSelectedNotes => {
+                    handle_delete_selected_notes(bot, message, chat, dialogue, state).await?
+                }
+                Topic::ConfirmBulkDelete => {
+                    handle_confirm_bulk_delete(bot, message, chat, dialogue, state).await?
                 }
                 _ => warn!("unkwnown topic"),
             }
@@ -145,8 +274,15 @@ pub async fn handle_callback(bot: Bot, query: CallbackQuery) -> Result<(), Reque
             Ok(())
         }
         Err(e) => {
-            println!("ERROR WHILE PARSING CALLBACK DATA: {:?}", e);
-            Ok(())
+            warn!(
+                "[HANDLE_CALLBACK] invalid callback data {:?}: {}",
+                raw_data, e
+            );
+
+            match message {
+                Some(msg) => commands::invalid_button_command(bot, msg).await,
+                None => Ok(()),
+            }
         }
     }
 }
@@ -156,21 +292,12 @@ async fn handle_delete_note(
     msg: Option<teloxide::types::Message>,
     target: Option<Chat>,
     payload: Option<Payload>,
+    dialogue: &ManagerDialogue,
 ) -> ResponseResult<()> {
     match target {
-        Some(chat) => {
-            match payload {
-                Some(data) => {
-                    warn!("Payload provided, but not needed");
-                    commands::delete_note_command(bot, msg.unwrap(), Some(data)).await?;
-                    Ok(())
-                }
-                None => {
-                    // no Payload provided
-                    commands::delete_note_command(bot, msg.unwrap(), None).await?;
-                    Ok(())
-                }
-            }
+        Some(_) => {
+            commands::delete_note_command(bot, msg.unwrap(), payload, dialogue).await?;
+            Ok(())
         }
         // No target Chat available
         None => {
@@ -181,6 +308,31 @@ async fn handle_delete_note(
 }
 
 async fn handle_confirm_delete(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match (target, state) {
+        (Some(_), State::ConfirmingDelete { note_id }) => {
+            info!("Deleting note: {:?}", note_id);
+            commands::confirm_delete_command(bot, msg.unwrap(), note_id, dialogue).await?;
+            Ok(())
+        }
+        (Some(_), state) => {
+            warn!("not confirming a deletion in {:?}", state);
+            Ok(())
+        }
+        // No target Chat available
+        (None, _) => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_list_all_notes(
     bot: &Bot,
     msg: Option<teloxide::types::Message>,
     target: Option<Chat>,
@@ -190,13 +342,14 @@ async fn handle_confirm_delete(
         Some(chat) => {
             match payload {
                 Some(data) => {
+                    // INFO: prev/next buttons turn the page of the list they are below
                     info!("Payload data is: {:?}", data);
-                    commands::confirm_delete_command(bot, msg.unwrap(), Some(data)).await?;
+                    commands::list_page_command(bot, msg.unwrap(), Some(data)).await?;
                     Ok(())
                 }
                 None => {
                     // no Payload provided
-                    commands::confirm_delete_command(bot, msg.unwrap(), None).await?;
+                    commands::list_command(bot, msg.unwrap()).await?;
                     Ok(())
                 }
             }
@@ -209,26 +362,35 @@ async fn handle_confirm_delete(
     }
 }
 
-async fn handle_list_all_notes(
+async fn handle_moderate_notes(
     bot: &Bot,
     msg: Option<teloxide::types::Message>,
     target: Option<Chat>,
     payload: Option<Payload>,
 ) -> ResponseResult<()> {
     match target {
-        Some(chat) => {
-            match payload {
-                Some(data) => {
-                    warn!("Payload provided, but not needed");
-                    commands::list_command(bot, msg.unwrap()).await?;
-                    Ok(())
-                }
-                None => {
-                    // no Payload provided
-                    commands::list_command(bot, msg.unwrap()).await?;
-                    Ok(())
-                }
-            }
+        Some(_) => {
+            commands::moderate_page_command(bot, msg.unwrap(), payload).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_show_note(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+) -> ResponseResult<()> {
+    match target {
+        Some(_) => {
+            commands::show_note_command(bot, msg.unwrap(), payload).await?;
+            Ok(())
         }
         // No target Chat available
         None => {
@@ -243,18 +405,20 @@ async fn handle_erase_all_notes(
     msg: Option<teloxide::types::Message>,
     target: Option<Chat>,
     payload: Option<Payload>,
+    dialogue: &ManagerDialogue,
 ) -> ResponseResult<()> {
     match target {
         Some(chat) => {
             match payload {
                 Some(data) => {
                     warn!("Payload provided, but not needed");
-                    commands::erase_all_notes_command(bot, msg.unwrap(), Some(data)).await?;
+                    commands::erase_all_notes_command(bot, msg.unwrap(), Some(data), dialogue)
+                        .await?;
                     Ok(())
                 }
                 None => {
                     // no Payload provided
-                    commands::erase_all_notes_command(bot, msg.unwrap(), None).await?;
+                    commands::erase_all_notes_command(bot, msg.unwrap(), None, dialogue).await?;
                     Ok(())
                 }
             }
@@ -272,19 +436,26 @@ async fn handle_confirm_erase_all_notes(
     msg: Option<teloxide::types::Message>,
     target: Option<Chat>,
     payload: Option<Payload>,
+    dialogue: &ManagerDialogue,
 ) -> ResponseResult<()> {
     match target {
         Some(chat) => {
             match payload {
                 Some(data) => {
                     warn!("Payload provided, but not needed");
-                    commands::confirm_erase_all_notes_command(bot, msg.unwrap(), Some(data))
-                        .await?;
+                    commands::confirm_erase_all_notes_command(
+                        bot,
+                        msg.unwrap(),
+                        Some(data),
+                        dialogue,
+                    )
+                    .await?;
                     Ok(())
                 }
                 None => {
                     // no Payload provided
-                    commands::confirm_erase_all_notes_command(bot, msg.unwrap(), None).await?;
+                    commands::confirm_erase_all_notes_command(bot, msg.unwrap(), None, dialogue)
+                        .await?;
                     Ok(())
                 }
             }
@@ -451,7 +622,7 @@ async fn handle_set_language(
             match payload {
                 Some(data) => {
                     info!("button data is: {:#?}", data);
-                    let selected_locale = Locale::from_str(data.to_string().as_str());
+                    let selected_locale = data.to_string().parse::<Locale>().unwrap_or_default();
                     info!("selected locale is: {:?}", selected_locale);
                     commands::set_language_command(bot, msg.unwrap(), selected_locale).await?;
                     Ok(())
@@ -470,3 +641,290 @@ async fn handle_set_language(
         }
     }
 }
+
+async fn handle_set_digest_muted(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+) -> ResponseResult<()> {
+    match target {
+        Some(_) => {
+            commands::set_digest_muted_command(bot, msg.unwrap(), payload).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_pick_recipient(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match (target, state) {
+        (Some(_), State::PickingRecipient { draft }) => {
+            commands::pick_recipient_command(bot, msg.unwrap(), payload, dialogue, draft).await?;
+            Ok(())
+        }
+        (Some(_), state) => {
+            warn!("not picking a recipient in {:?}", state);
+            Ok(())
+        }
+        // No target Chat available
+        (None, _) => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_skip_caption(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match (target, state) {
+        (Some(_), State::WritingCaption { draft }) => {
+            commands::confirm_note_page_command(bot, msg.unwrap(), dialogue, draft).await?;
+            Ok(())
+        }
+        (Some(_), state) => {
+            warn!("not writing a caption in {:?}", state);
+            Ok(())
+        }
+        // No target Chat available
+        (None, _) => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_confirm_note(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match (target, state) {
+        (Some(_), State::ConfirmingNote { draft }) => {
+            commands::confirm_note_command(bot, msg.unwrap(), dialogue, draft).await?;
+            Ok(())
+        }
+        (Some(_), state) => {
+            warn!("not confirming a note in {:?}", state);
+            Ok(())
+        }
+        // No target Chat available
+        (None, _) => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_cancel_dialogue(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match target {
+        // INFO: leaving a selection goes back to the list it was made on
+        Some(_) if state.is_selecting() => {
+            exit_dialogue(dialogue, &state).await?;
+            commands::list_command(bot, msg.unwrap()).await?;
+            Ok(())
+        }
+        Some(_) => {
+            exit_dialogue(dialogue, &state).await?;
+            commands::start_command(bot, msg.unwrap()).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_select_notes(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match target {
+        Some(_) => {
+            // INFO: prev/next buttons of a selection keep the notes already selected
+            let selected = match state {
+                State::SelectingNotes { selected, .. } => selected,
+                _ => vec![],
+            };
+            commands::select_notes_command(bot, msg.unwrap(), payload, dialogue, selected).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_toggle_note(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match (target, state, payload) {
+        (Some(_), State::SelectingNotes { selected, page }, Some(Payload::NoteId(note_id))) => {
+            commands::toggle_note_command(bot, msg.unwrap(), note_id, dialogue, selected, page)
+                .await?;
+            Ok(())
+        }
+        (Some(_), state, payload) => {
+            warn!("cannot toggle {:?} in {:?}", payload, state);
+            Ok(())
+        }
+        // No target Chat available
+        (None, _, _) => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_delete_selected_notes(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match (target, state) {
+        (Some(_), State::SelectingNotes { selected, .. }) => {
+            commands::delete_selected_notes_command(bot, msg.unwrap(), dialogue, selected).await?;
+            Ok(())
+        }
+        (Some(_), state) => {
+            warn!("not selecting notes in {:?}", state);
+            Ok(())
+        }
+        // No target Chat available
+        (None, _) => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_confirm_bulk_delete(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    dialogue: &ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match (target, state) {
+        (Some(_), State::ConfirmingBulkDelete { selected }) => {
+            info!("Deleting notes: {:?}", selected);
+            commands::confirm_bulk_delete_command(bot, msg.unwrap(), dialogue, selected).await?;
+            Ok(())
+        }
+        (Some(_), state) => {
+            warn!("not confirming a bulk deletion in {:?}", state);
+            Ok(())
+        }
+        // No target Chat available
+        (None, _) => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bincode::Options;
+
+    use super::*;
+
+    // INFO: first byte of the codec encoding, the variant index below 251
+    fn variant_index<T: Serialize>(value: &T) -> u8 {
+        bincode::DefaultOptions::new()
+            .with_varint_encoding()
+            .serialize(value)
+            .unwrap()[0]
+    }
+
+    #[test]
+    fn topics_keep_their_index() {
+        let topics = [
+            Topic::ListAllNotes,
+            Topic::EraseAllNotes,
+            Topic::ConfirmEraseAllNotes,
+            Topic::DeleteNote,
+            Topic::ConfirmDelete,
+            Topic::GoHomePage,
+            Topic::GoUploadPage,
+            Topic::GoCreditsPage,
+            Topic::GoLanguagePage,
+            Topic::GoHelpPage,
+            Topic::SetLanguage,
+            Topic::ChooseRecipient,
+            Topic::SetRecipient,
+            Topic::SetDigestMuted,
+            Topic::ModerateNotes,
+            Topic::ShowNote,
+            Topic::PickRecipient,
+            Topic::SkipCaption,
+            Topic::ConfirmNote,
+            Topic::CancelDialogue,
+            Topic::SelectNotes,
+            Topic::ToggleNote,
+            Topic::DeleteSelectedNotes,
+            Topic::ConfirmBulkDelete,
+        ];
+
+        for (index, topic) in topics.iter().enumerate() {
+            assert_eq!(variant_index(topic) as usize, index, "{:?}", topic);
+        }
+    }
+
+    #[test]
+    fn payloads_keep_their_index() {
+        let payloads = [
+            Payload::Text("text".to_string()),
+            Payload::Username("username".to_string()),
+            Payload::UserId(1),
+            Payload::NoteId(1),
+            Payload::NoteRecipient(1, 2),
+            Payload::Muted(true),
+            Payload::PageAfter(1),
+            Payload::PageBefore(1),
+        ];
+
+        for (index, payload) in payloads.iter().enumerate() {
+            assert_eq!(variant_index(payload) as usize, index, "{:?}", payload);
+        }
+    }
+}
@@ -1,30 +1,33 @@
 use std::io::Error;
 
+use cheer_up_core::error::ErrorCode;
 use log::{debug, info};
 use rust_i18n::set_locale;
 use serde_json::json;
 use teloxide::{
-    payloads::SendMessageSetters,
+    payloads::{AnswerCallbackQuerySetters, SendMessageSetters},
     prelude::*,
-    types::{
-        InlineKeyboardButton, InlineKeyboardMarkup, InputFile, KeyboardButton, KeyboardMarkup,
-        ParseMode,
-    },
+    types::{ChatAction, InlineKeyboardMarkup, ParseMode, VideoNote},
     utils::command::BotCommands,
 };
 use tokio::fs;
 
 use crate::{
     callbacks::{Payload, QueryData, Topic},
+    config,
+    dialogues::{ManagerDialogue, NoteDraft, State},
+    digests::{get_digest_settings, set_digest_muted},
+    groups::{create_group, get_user_groups, get_user_peers, join_group},
     keyboards::{self, upload_page},
     locale::{get_user_locale_by_user_id, set_user_locale_by_user_id, Locale},
+    nav,
     stats::get_stats,
     templates::Templates,
-    user::{get_user, get_user_by_id, get_user_by_telegram_id, save_user_to_db, UserId},
-    utils::{get_user_folder_path, get_user_folder_path_by_user},
+    user::{get_user, get_user_by_id, get_user_by_telegram_id, save_user_to_db, User, UserId},
     videonotes::{
-        delete_all_user_vnotes, delete_vnote_from_db, get_author_vnote_list_from_db,
-        get_vnote_list_from_db,
+        delete_all_user_vnotes, delete_vnote_from_db, delete_vnotes_from_db, find_vnote,
+        get_all_vnotes_page_from_db, get_author_vnote_page_from_db, get_vnote_reactions,
+        save_vnote_to_db, send_vnote, upload_vnote, LIST_PAGE_SIZE, MAX_NOTE_CAPTION_LEN,
     },
 };
 
@@ -43,10 +46,24 @@ pub enum Command {
     Help,
     #[command(description = "Show credits and code repo links")]
     Credits,
+    #[command(description = "Get invite codes of your groups, or create a new group")]
+    Invite(String),
+    #[command(description = "Join a group of friends with an invite code")]
+    Join(String),
+    #[command(description = "Turn on or mute digests of how friends received your notes")]
+    Notifications,
+    #[command(description = "Review and delete the video notes of every user, admins only")]
+    Moderate,
 }
 
 impl Command {
     pub fn parse_str(cmd: &str) -> Option<Command> {
+        // INFO: commands taking an argument, i.e. `/join <invite code>`
+        let (cmd, arg) = match cmd.trim().split_once(char::is_whitespace) {
+            Some((cmd, arg)) => (cmd, arg.trim()),
+            None => (cmd.trim(), ""),
+        };
+
         match cmd {
             "/start" => Some(Command::Start),
             "/upload" => Some(Command::Upload),
@@ -54,6 +71,10 @@ impl Command {
             "/language" => Some(Command::Language),
             "/help" => Some(Command::Help),
             "/credits" => Some(Command::Credits),
+            "/join" => Some(Command::Join(arg.to_string())),
+            "/invite" => Some(Command::Invite(arg.to_string())),
+            "/notifications" => Some(Command::Notifications),
+            "/moderate" => Some(Command::Moderate),
             _ => None,
         }
     }
@@ -67,6 +88,10 @@ pub async fn handle_commands(bot: Bot, cmd: Command, msg: Message) -> ResponseRe
         Command::Language => language_command(&bot, msg).await?,
         Command::Help => help_command(&bot, msg).await?,
         Command::Credits => credits_command(&bot, msg).await?,
+        Command::Invite(name) => invite_command(&bot, msg, name).await?,
+        Command::Join(invite_code) => join_command(&bot, msg, invite_code).await?,
+        Command::Notifications => notifications_command(&bot, msg).await?,
+        Command::Moderate => moderate_command(&bot, msg).await?,
     }
 
     Ok(())
@@ -87,10 +112,7 @@ pub async fn start_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::start_page(&remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -98,6 +120,7 @@ pub async fn delete_note_command(
     bot: &Bot,
     msg: Message,
     query_data: Option<Payload>,
+    dialogue: &ManagerDialogue,
 ) -> ResponseResult<()> {
     let data = query_data.unwrap_or(Payload::Text("none".to_string()));
     info!("[DELETE_NOTE_COMMAND] data is: {:?}", data);
@@ -113,30 +136,26 @@ pub async fn delete_note_command(
 
     let locale_str = remote_locale.to_string();
 
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .await?;
-
     info!("[DELETE_NOTE_COMMAND] deleting vnote with id: {:?}", &data);
 
+    // INFO: the confirmation only deletes the note asked for here
+    if let Payload::NoteId(note_id) = data {
+        dialogue.update(State::ConfirmingDelete { note_id }).await?;
+    }
+
     let template = Templates::DeleteNotePage(data.to_string());
 
     let keyboard = keyboards::delete_note_page(Some(data), &remote_locale);
 
-    // bot.send_message(msg.chat.id, template.render())
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
 pub async fn confirm_delete_command(
     bot: &Bot,
     msg: Message,
-    query_data: Option<Payload>,
+    vnote_id: i64,
+    dialogue: &ManagerDialogue,
 ) -> ResponseResult<()> {
     let user = get_user(&msg.chat).await?;
     info!("[CONFIRM_DELETE_COMMAND] user is: {:?}", user);
@@ -149,72 +168,40 @@ pub async fn confirm_delete_command(
 
     let locale_str = remote_locale.to_string();
 
-    if query_data.is_none() {
-        let keyboard = keyboards::delete_note_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
-            Templates::ErrorDeleteNotePage.render(&locale_str),
-        )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
-        return Ok(());
-    }
-
-    let data = query_data.unwrap();
+    dialogue.exit().await?;
 
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
+    bot.send_chat_action(msg.chat.id, ChatAction::Typing)
         .await?;
 
-    info!("[CONFIRM_DELETE_COMMAND] deleting vnote with id: {}", &data);
-
-    let parsed_data = data.to_string().parse::<i64>();
-    if parsed_data.is_err() {
-        let keyboard = keyboards::delete_note_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
-            Templates::ErrorDeleteNotePage.render(&locale_str),
-        )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
-        return Ok(());
-    }
-    let vnote_id = parsed_data.unwrap();
-    // let deleted_note = delete_vnote_from_db(&vnote_id).await?;
-    debug!("vnote_id is : {}", vnote_id);
-    let deleted_note = delete_vnote_from_db(&vnote_id).await;
-
-    if deleted_note.is_err() {
-        info!(
-            "[CONFIRM_DELETE_COMMAND] error deleting vnote with id: {}",
-            &vnote_id
-        );
-        let keyboard = keyboards::delete_note_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
-            Templates::ErrorDeleteNotePage.render(&locale_str),
-        )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
-        return Ok(());
-    }
+    info!(
+        "[CONFIRM_DELETE_COMMAND] deleting vnote with id: {}",
+        &vnote_id
+    );
 
-    let unwrapped_deleted_note = deleted_note.unwrap();
+    let deleted_note = delete_vnote_from_db(&vnote_id, &user.id).await;
 
-    let template = Templates::SuccessDeleteNotePage(unwrapped_deleted_note.note);
+    let template = match deleted_note {
+        Ok(Ok(deleted_note)) => Templates::SuccessDeleteNotePage(deleted_note.note),
+        Ok(Err(ErrorCode::NotNoteOwner)) => Templates::NotNoteOwnerPage,
+        Ok(Err(code)) => {
+            info!(
+                "[CONFIRM_DELETE_COMMAND] vnote {} not deleted: {:?}",
+                &vnote_id, code
+            );
+            Templates::ErrorDeleteNotePage
+        }
+        Err(e) => {
+            info!(
+                "[CONFIRM_DELETE_COMMAND] error deleting vnote with id {}: {}",
+                &vnote_id, e
+            );
+            Templates::ErrorDeleteNotePage
+        }
+    };
 
     let keyboard = keyboards::delete_note_result_page(&remote_locale);
 
-    // bot.send_message(msg.chat.id, template.render())
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -222,6 +209,7 @@ pub async fn erase_all_notes_command(
     bot: &Bot,
     msg: Message,
     query_data: Option<Payload>,
+    dialogue: &ManagerDialogue,
 ) -> ResponseResult<()> {
     let data = query_data.unwrap_or(Payload::Text("none".to_string()));
     info!("[erase_all_notes_COMMAND] data is: {:?}", data);
@@ -242,15 +230,13 @@ pub async fn erase_all_notes_command(
         &data
     );
 
+    dialogue.update(State::ConfirmingEraseAll).await?;
+
     let template = Templates::EraseAllNotesPage(data.to_string());
 
     let keyboard = keyboards::erase_all_notes_page(Some(data), &remote_locale);
 
-    // bot.send_message(msg.chat.id, template.render())
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -258,6 +244,7 @@ pub async fn confirm_erase_all_notes_command(
     bot: &Bot,
     msg: Message,
     query_data: Option<Payload>,
+    dialogue: &ManagerDialogue,
 ) -> ResponseResult<()> {
     let user = get_user(&msg.chat).await?;
     info!("[CONFIRM_ERASE_ALL_NOTES_COMMAND] user is: {:?}", user);
@@ -270,22 +257,21 @@ pub async fn confirm_erase_all_notes_command(
 
     let locale_str = remote_locale.to_string();
 
+    dialogue.exit().await?;
+
     if query_data.is_none() {
         let keyboard = keyboards::erase_all_notes_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
+        nav::show_page(
+            bot,
+            &msg,
             Templates::ErrorEraseAllNotesPage.render(&locale_str),
+            keyboard,
         )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
         .await?;
         return Ok(());
     }
 
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
+    bot.send_chat_action(msg.chat.id, ChatAction::Typing)
         .await?;
 
     let data = query_data.unwrap();
@@ -298,12 +284,12 @@ pub async fn confirm_erase_all_notes_command(
     let parsed_data = data.clone().number();
     if parsed_data.is_none() {
         let keyboard = keyboards::erase_all_notes_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
+        nav::show_page(
+            bot,
+            &msg,
             Templates::ErrorEraseAllNotesPage.render(&locale_str),
+            keyboard,
         )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
         .await?;
         return Ok(());
     }
@@ -315,12 +301,12 @@ pub async fn confirm_erase_all_notes_command(
 
     if result.is_err() {
         let keyboard = keyboards::erase_all_notes_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
+        nav::show_page(
+            bot,
+            &msg,
             Templates::ErrorEraseAllNotesPage.render(&locale_str),
+            keyboard,
         )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
         .await?;
         return Ok(());
     }
@@ -329,62 +315,224 @@ pub async fn confirm_erase_all_notes_command(
 
     let keyboard = keyboards::erase_all_notes_result_page(&remote_locale);
 
-    // bot.send_message(msg.chat.id, template.render())
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
-pub async fn upload_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+/// Starts the upload flow of the video note `vnote` sent in `msg`, a note sent
+/// in the middle of another flow replaces it.
+pub async fn receive_vnote_command(
+    bot: &Bot,
+    msg: Message,
+    vnote: &VideoNote,
+    dialogue: &ManagerDialogue,
+) -> ResponseResult<()> {
     let user = get_user(&msg.chat).await?;
-    info!("[UPLOAD_COMMAND] user is: {:?}", user);
+    info!("[RECEIVE_VNOTE_COMMAND] user is: {:?}", user);
 
     let remote_locale = get_user_locale_by_user_id(&user.id).await?;
-    info!("[UPLOAD_COMMAND] remote_locale is: {:?}", remote_locale);
+    info!(
+        "[RECEIVE_VNOTE_COMMAND] remote_locale is: {:?}",
+        remote_locale
+    );
 
     let locale_str = remote_locale.to_string();
 
-    let vnote_list = get_vnote_list_from_db(&msg.chat).await?;
-    println!("vnote_list is: {:?}", vnote_list);
+    let peers = get_user_peers(&user.id).await?;
+    info!("[RECEIVE_VNOTE_COMMAND] peers are: {:?}", peers);
 
-    let stats = get_stats().await?;
+    let draft = NoteDraft::new(vnote.clone());
+    dialogue.update(State::PickingRecipient { draft }).await?;
 
-    let template = Templates::UploadPage(
-        msg.chat.username().unwrap_or("Unknown user").to_string(),
-        // "42".to_string(),
-        stats.total_videonotes.to_string(),
-        stats.users.len().to_string(),
-        stats.users,
+    let template = Templates::PickRecipientPage;
+
+    let keyboard = keyboards::pick_recipient_page(&peers, &remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn pick_recipient_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+    dialogue: &ManagerDialogue,
+    draft: NoteDraft,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[PICK_RECIPIENT_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!(
+        "[PICK_RECIPIENT_COMMAND] remote_locale is: {:?}",
+        remote_locale
     );
 
-    let keyboard = upload_page(None, &remote_locale);
+    let locale_str = remote_locale.to_string();
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    // INFO: EVERYONE has no payload
+    let recipient_id = match query_data {
+        Some(Payload::UserId(recipient_id)) => Some(recipient_id),
+        _ => None,
+    };
+    info!("[PICK_RECIPIENT_COMMAND] recipient is: {:?}", recipient_id);
+
+    let draft = NoteDraft {
+        recipient_id,
+        ..draft
+    };
+    dialogue.update(State::WritingCaption { draft }).await?;
+
+    let template = Templates::WriteCaptionPage(MAX_NOTE_CAPTION_LEN);
+
+    let keyboard = keyboards::write_caption_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
 
-pub async fn confirm_upload_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+/// Adds the caption typed in `msg` to the note being uploaded.
+pub async fn caption_command(
+    bot: &Bot,
+    msg: Message,
+    dialogue: &ManagerDialogue,
+    draft: NoteDraft,
+) -> ResponseResult<()> {
+    let caption = msg.text().unwrap_or_default().trim().to_string();
+    info!("[CAPTION_COMMAND] caption is: {:?}", caption);
+
+    if caption.chars().count() > MAX_NOTE_CAPTION_LEN {
+        let user = get_user(&msg.chat).await?;
+        let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+
+        let locale_str = remote_locale.to_string();
+
+        let template = Templates::CaptionTooLongPage(MAX_NOTE_CAPTION_LEN);
+
+        let keyboard = keyboards::write_caption_page(&remote_locale);
+
+        nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+        return Ok(());
+    }
+
+    let draft = NoteDraft {
+        caption: Some(caption).filter(|caption| !caption.is_empty()),
+        ..draft
+    };
+
+    confirm_note_page_command(bot, msg, dialogue, draft).await
+}
+
+/// Shows the note being uploaded for a last check, with its recipient and caption.
+pub async fn confirm_note_page_command(
+    bot: &Bot,
+    msg: Message,
+    dialogue: &ManagerDialogue,
+    draft: NoteDraft,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[CONFIRM_NOTE_PAGE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!(
+        "[CONFIRM_NOTE_PAGE_COMMAND] remote_locale is: {:?}",
+        remote_locale
+    );
+
+    let locale_str = remote_locale.to_string();
+
+    let recipient = match draft.recipient_id {
+        Some(recipient_id) => Some(get_user_by_id(&recipient_id).await?.username),
+        None => None,
+    };
+
+    let template = Templates::ConfirmNotePage(recipient, draft.caption.clone());
+
+    dialogue.update(State::ConfirmingNote { draft }).await?;
+
+    let keyboard = keyboards::confirm_note_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+/// Saves the note being uploaded, only now the video note gets copied to the media store.
+pub async fn confirm_note_command(
+    bot: &Bot,
+    msg: Message,
+    dialogue: &ManagerDialogue,
+    draft: NoteDraft,
+) -> ResponseResult<()> {
     let user = get_user(&msg.chat).await?;
-    info!("[CONFIRM_UPLOAD_COMMAND] user is: {:?}", user);
+    info!("[CONFIRM_NOTE_COMMAND] user is: {:?}", user);
 
     let remote_locale = get_user_locale_by_user_id(&user.id).await?;
     info!(
-        "[CONFIRM_UPLOAD_COMMAND] remote_locale is: {:?}",
+        "[CONFIRM_NOTE_COMMAND] remote_locale is: {:?}",
         remote_locale
     );
 
     let locale_str = remote_locale.to_string();
 
-    let vnote_list = get_vnote_list_from_db(&msg.chat).await?;
-    debug!("[CONFIRM_UPLOAD_COMMAND] vnote_list is: {:?}", vnote_list);
+    // INFO: left first, so that the note cannot be saved twice
+    dialogue.exit().await?;
+
+    bot.send_chat_action(msg.chat.id, ChatAction::UploadVideoNote)
+        .await?;
+
+    let keyboard = keyboards::upload_result_page(&remote_locale);
 
-    let stats = get_stats().await?;
+    // INFO: copy vnote to the media store, its file_id is useless to the cheer-up bot
+    let media_key = match upload_vnote(bot, &draft.vnote, &msg.chat).await {
+        Ok(key) => key,
+        Err(e) => {
+            info!(
+                "[CONFIRM_NOTE_COMMAND] an error occurred while saving note: {}",
+                e
+            );
+            nav::show_page(
+                bot,
+                &msg,
+                Templates::ErrorUploadPage.render(&locale_str),
+                keyboard,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let template = match save_vnote_to_db(&draft, &msg.chat, media_key).await {
+        Ok(saved_vnote) => {
+            info!("[CONFIRM_NOTE_COMMAND] saved note: {:?}", saved_vnote);
+            Templates::SuccessUploadPage
+        }
+        Err(e) => {
+            info!(
+                "[CONFIRM_NOTE_COMMAND] an error occurred while saving note: {}",
+                e
+            );
+            Templates::ErrorUploadPage
+        }
+    };
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn upload_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[UPLOAD_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[UPLOAD_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    let stats = get_stats(&user.id).await?;
 
     let template = Templates::UploadPage(
         msg.chat.username().unwrap_or("Unknown user").to_string(),
@@ -396,10 +544,7 @@ pub async fn confirm_upload_command(bot: &Bot, msg: Message) -> ResponseResult<(
 
     let keyboard = upload_page(None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
@@ -413,48 +558,323 @@ pub async fn list_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let locale_str = remote_locale.to_string();
 
-    // show loading indicator
-    let template = Templates::LoadingPage;
+    let (template, keyboard) = list_page(&user, None, &remote_locale).await?;
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+/// Turns the page of the `/list` message `msg`, editing it in place.
+pub async fn list_page_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[LIST_PAGE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[LIST_PAGE_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    let (template, keyboard) = list_page(&user, query_data, &remote_locale).await?;
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await
+}
+
+async fn list_page(
+    user: &User,
+    cursor: Option<Payload>,
+    locale: &Locale,
+) -> ResponseResult<(Templates, InlineKeyboardMarkup)> {
+    let (after, before) = page_cursors(cursor.clone());
+
+    let vnote_page = get_author_vnote_page_from_db(&user.id, after, before).await?;
+    debug!("[LIST_PAGE] vnote_page is: {:?}", vnote_page);
+
+    let (page, pages) = vnote_page.position(LIST_PAGE_SIZE);
+
+    let template = Templates::ListPage(
+        user.username.clone(),
+        vnote_page.total.to_string(),
+        page as usize,
+        pages as usize,
+    );
+    let keyboard = keyboards::list_notes_page(
+        &vnote_page,
+        cursor,
+        Some(Payload::UserId(user.id)),
+        None,
+        locale,
+    );
+
+    Ok((template, keyboard))
+}
+
+/// Shows the `/list` page at cursor `page` with a checkbox on each note,
+/// `selected` notes are kept selected across pages.
+pub async fn select_notes_command(
+    bot: &Bot,
+    msg: Message,
+    page: Option<Payload>,
+    dialogue: &ManagerDialogue,
+    selected: Vec<i64>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[SELECT_NOTES_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!(
+        "[SELECT_NOTES_COMMAND] remote_locale is: {:?}",
+        remote_locale
+    );
+
+    let locale_str = remote_locale.to_string();
+
+    let (after, before) = page_cursors(page.clone());
+
+    let vnote_page = get_author_vnote_page_from_db(&user.id, after, before).await?;
+    debug!("[SELECT_NOTES_COMMAND] vnote_page is: {:?}", vnote_page);
+
+    let (page_number, pages) = vnote_page.position(LIST_PAGE_SIZE);
+
+    let template = Templates::SelectNotesPage(selected.len(), page_number as usize, pages as usize);
+    let keyboard = keyboards::select_notes_page(&vnote_page, &selected, &remote_locale);
+
+    dialogue
+        .update(State::SelectingNotes { selected, page })
+        .await?;
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await
+}
+
+pub async fn toggle_note_command(
+    bot: &Bot,
+    msg: Message,
+    note_id: i64,
+    dialogue: &ManagerDialogue,
+    mut selected: Vec<i64>,
+    page: Option<Payload>,
+) -> ResponseResult<()> {
+    match selected.iter().position(|id| *id == note_id) {
+        Some(index) => {
+            selected.remove(index);
+        }
+        None => selected.push(note_id),
+    }
+    info!("[TOGGLE_NOTE_COMMAND] selected notes are: {:?}", selected);
+
+    select_notes_command(bot, msg, page, dialogue, selected).await
+}
+
+pub async fn delete_selected_notes_command(
+    bot: &Bot,
+    msg: Message,
+    dialogue: &ManagerDialogue,
+    selected: Vec<i64>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[DELETE_SELECTED_NOTES_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!(
+        "[DELETE_SELECTED_NOTES_COMMAND] remote_locale is: {:?}",
+        remote_locale
+    );
+
+    let locale_str = remote_locale.to_string();
+
+    let template = Templates::BulkDeletePage(selected.clone());
+
+    let keyboard = keyboards::bulk_delete_page(&remote_locale);
+
+    // INFO: the confirmation only deletes the notes listed on its page
+    dialogue
+        .update(State::ConfirmingBulkDelete { selected })
         .await?;
 
-    let vnote_list = get_author_vnote_list_from_db(&msg.chat).await?;
-    debug!("vnote_list is: {:?}", vnote_list);
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await
+}
+
+pub async fn confirm_bulk_delete_command(
+    bot: &Bot,
+    msg: Message,
+    dialogue: &ManagerDialogue,
+    selected: Vec<i64>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[CONFIRM_BULK_DELETE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!(
+        "[CONFIRM_BULK_DELETE_COMMAND] remote_locale is: {:?}",
+        remote_locale
+    );
+
+    let locale_str = remote_locale.to_string();
 
-    for vnote in &vnote_list {
-        let user = get_user_by_id(&vnote.user_id).await?;
+    dialogue.exit().await?;
 
-        let mut user_folder = get_user_folder_path_by_user(&user);
+    bot.send_chat_action(msg.chat.id, ChatAction::Typing)
+        .await?;
 
-        // this is needed because first 2 users in database
-        // are fake for boilerplate on start up
-        if vnote.user_id == 1 || vnote.user_id == 2 {
-            user_folder = format!(
-                "../_common_data/videonotes/{}_{}",
-                user.telegram_id, user.username
+    info!(
+        "[CONFIRM_BULK_DELETE_COMMAND] deleting vnotes with ids: {:?}",
+        &selected
+    );
+
+    // INFO: the api deletes every note or none of them
+    let template = match delete_vnotes_from_db(&selected, &user.id).await {
+        Ok(Ok(deleted)) => Templates::SuccessBulkDeletePage(deleted.len()),
+        Ok(Err(ErrorCode::NotNoteOwner)) => Templates::NotNoteOwnerPage,
+        Ok(Err(code)) => {
+            info!(
+                "[CONFIRM_BULK_DELETE_COMMAND] vnotes {:?} not deleted: {:?}",
+                &selected, code
             );
+            Templates::ErrorDeleteNotePage
         }
-        debug!("user_folder after user check is: {}", user_folder);
+        Err(e) => {
+            info!(
+                "[CONFIRM_BULK_DELETE_COMMAND] error deleting vnotes with ids {:?}: {}",
+                &selected, e
+            );
+            Templates::ErrorDeleteNotePage
+        }
+    };
+
+    let keyboard = keyboards::delete_note_result_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+    Ok(())
+}
 
-        let file_path = format!("{}/{}", user_folder, vnote.file_name);
-        debug!("file_path is: {}", file_path);
+pub async fn moderate_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[MODERATE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[MODERATE_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    if !user.is_admin() {
+        bot.send_message(msg.chat.id, Templates::NotAdminPage.render(&locale_str))
+            .parse_mode(ParseMode::Html)
+            .await?;
+        return Ok(());
+    }
+
+    let (template, keyboard) = moderate_page(None, &remote_locale).await?;
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+/// Turns the page of the `/moderate` message `msg`, editing it in place.
+pub async fn moderate_page_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[MODERATE_PAGE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!(
+        "[MODERATE_PAGE_COMMAND] remote_locale is: {:?}",
+        remote_locale
+    );
+
+    let locale_str = remote_locale.to_string();
 
-        let keyboard = keyboards::vnote_entry(Some(Payload::NoteId(vnote.id)), &remote_locale);
-        bot.send_video_note(msg.chat.id, InputFile::file(file_path))
-            .reply_markup(keyboard)
+    // INFO: the role may have been revoked since the list was sent
+    if !user.is_admin() {
+        bot.send_message(msg.chat.id, Templates::NotAdminPage.render(&locale_str))
+            .parse_mode(ParseMode::Html)
             .await?;
+        return Ok(());
+    }
+
+    let (template, keyboard) = moderate_page(query_data, &remote_locale).await?;
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await
+}
+
+async fn moderate_page(
+    cursor: Option<Payload>,
+    locale: &Locale,
+) -> ResponseResult<(Templates, InlineKeyboardMarkup)> {
+    let (after, before) = page_cursors(cursor);
+
+    // INFO: deleting goes through the same confirmation as `/list`, the api
+    //       lets admins delete notes they do not own
+    let vnote_page = get_all_vnotes_page_from_db(after, before).await?;
+    debug!("[MODERATE_PAGE] vnote_page is: {:?}", vnote_page);
+
+    let (page, pages) = vnote_page.position(LIST_PAGE_SIZE);
+
+    let template =
+        Templates::ModeratePage(vnote_page.total.to_string(), page as usize, pages as usize);
+    let keyboard = keyboards::moderate_page(&vnote_page, locale);
+
+    Ok((template, keyboard))
+}
+
+fn page_cursors(cursor: Option<Payload>) -> (Option<i64>, Option<i64>) {
+    match cursor {
+        Some(Payload::PageAfter(cursor)) => (Some(cursor), None),
+        Some(Payload::PageBefore(cursor)) => (None, Some(cursor)),
+        _ => (None, None),
     }
+}
 
-    let template = Templates::ListPage(user.username, vnote_list.len().to_string());
+/// Sends a note picked from a `/list` or `/moderate` page, with its delete button
+/// and the reactions friends left on it.
+pub async fn show_note_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[SHOW_NOTE_COMMAND] user is: {:?}", user);
 
-    let keyboard = keyboards::list_notes_page(Some(Payload::UserId(user.id)), None, &remote_locale);
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[SHOW_NOTE_COMMAND] remote_locale is: {:?}", remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
+    let locale_str = remote_locale.to_string();
+
+    let vnote = match query_data {
+        Some(Payload::NoteId(note_id)) => find_vnote(&note_id).await?,
+        _ => None,
+    };
+
+    let Some(vnote) = vnote else {
+        bot.send_message(
+            msg.chat.id,
+            Templates::NoteUnavailablePage.render(&locale_str),
+        )
         .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
         .await?;
+        return Ok(());
+    };
+
+    let keyboard = keyboards::vnote_entry(Some(Payload::NoteId(vnote.id)), &remote_locale);
+    send_vnote(bot, msg.chat.id, &vnote, Some(keyboard)).await?;
+
+    // INFO: video notes have no caption, reactions go in a message right below
+    let reactions = get_vnote_reactions(&vnote.id).await?;
+    debug!("[SHOW_NOTE_COMMAND] reactions are: {:?}", reactions);
+
+    if let Some(reactions) = reactions.filter(|reactions| !reactions.is_empty()) {
+        let template = Templates::NoteReactionsEntry(reactions);
+        bot.send_message(msg.chat.id, template.render(&locale_str))
+            .parse_mode(ParseMode::Html)
+            .await?;
+    }
 
     Ok(())
 }
@@ -472,11 +892,67 @@ pub async fn language_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::language_page(&remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .disable_web_page_preview(true)
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+    Ok(())
+}
+
+/// Answers taps on buttons whose data was forged, or kept in memory before a restart.
+pub async fn invalid_button_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[INVALID_BUTTON_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+
+    let locale_str = remote_locale.to_string();
+
+    let template = Templates::InvalidButtonPage;
+
+    let keyboard = keyboards::delete_note_result_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+    Ok(())
+}
+
+/// Answers taps on buttons of a page left behind by a newer one, and removes them.
+pub async fn stale_button_command(bot: &Bot, msg: Message, query_id: String) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[STALE_BUTTON_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+
+    let locale_str = remote_locale.to_string();
+
+    let template = Templates::StaleButtonAlert;
+
+    bot.answer_callback_query(query_id)
+        .text(template.render(&locale_str))
+        .show_alert(true)
+        .await?;
+
+    nav::drop_keyboard(bot, msg.chat.id, msg.id).await;
+    Ok(())
+}
+
+/// Answers taps on buttons of another step of the current flow, i.e. CONFIRM tapped twice.
+pub async fn out_of_order_button_command(
+    bot: &Bot,
+    msg: Message,
+    query_id: String,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[OUT_OF_ORDER_BUTTON_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+
+    let locale_str = remote_locale.to_string();
+
+    let template = Templates::OutOfOrderButtonAlert;
+
+    bot.answer_callback_query(query_id)
+        .text(template.render(&locale_str))
+        .show_alert(true)
         .await?;
+
     Ok(())
 }
 
@@ -493,10 +969,7 @@ pub async fn help_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::help_page(None, None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -513,11 +986,112 @@ pub async fn credits_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::credits_page(None, None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .disable_web_page_preview(true)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn notifications_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[NOTIFICATIONS_COMMAND] user is: {:?}", user);
+
+    let settings = get_digest_settings(&user.id).await?;
+    info!("[NOTIFICATIONS_COMMAND] settings are: {:?}", settings);
+
+    send_notifications_page(bot, &msg, &user.id, settings.muted).await
+}
+
+pub async fn set_digest_muted_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[SET_DIGEST_MUTED_COMMAND] user is: {:?}", user);
+
+    let settings = match query_data {
+        Some(Payload::Muted(muted)) => set_digest_muted(&user.id, muted).await?,
+        _ => get_digest_settings(&user.id).await?,
+    };
+    info!("[SET_DIGEST_MUTED_COMMAND] settings are: {:?}", settings);
+
+    send_notifications_page(bot, &msg, &user.id, settings.muted).await
+}
+
+async fn send_notifications_page(
+    bot: &Bot,
+    msg: &Message,
+    user_id: &i64,
+    muted: bool,
+) -> ResponseResult<()> {
+    let remote_locale = get_user_locale_by_user_id(user_id).await?;
+
+    let locale_str = remote_locale.to_string();
+
+    let template = Templates::NotificationsPage(muted, config::get().digest_period_days);
+
+    let keyboard = keyboards::notifications_page(muted, &remote_locale);
+
+    nav::show_page(bot, msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn join_command(bot: &Bot, msg: Message, invite_code: String) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[JOIN_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[JOIN_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    let group = match invite_code.trim() {
+        "" => None,
+        code => join_group(code, &user.id).await?,
+    };
+    info!("[JOIN_COMMAND] joined group is: {:?}", group);
+
+    let template = match group {
+        Some(group) => Templates::JoinGroupPage(group.name),
+        None => Templates::ErrorJoinGroupPage,
+    };
+
+    let keyboard = keyboards::group_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn invite_command(bot: &Bot, msg: Message, group_name: String) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[INVITE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[INVITE_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    // INFO: `/invite <name>` creates a new group, plain `/invite` lists the user groups
+    //      and creates a first one if the user has none yet
+    let mut groups = get_user_groups(&user.id).await?;
+    let group_name = group_name.trim();
+    if !group_name.is_empty() || groups.is_empty() {
+        let name = match group_name {
+            "" => format!("{}'s friends", user.username),
+            name => name.to_string(),
+        };
+        let group = create_group(&name, &user.id).await?;
+        info!("[INVITE_COMMAND] created group: {:?}", group);
+        groups.push(group);
+    }
+
+    let template = Templates::InvitePage(groups);
+
+    let keyboard = keyboards::group_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
@@ -541,11 +1115,7 @@ pub async fn set_language_command(bot: &Bot, msg: Message, locale: Locale) -> Re
     let rendered_template = template.render(&locale_str);
     debug!("rendered_template: {:?}", rendered_template);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .disable_web_page_preview(true)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
@@ -0,0 +1,17 @@
+use std::sync::OnceLock;
+
+pub use cheer_up_core::config::{BotArgs, BotConfig, StorageMode};
+
+static CONFIG: OnceLock<BotConfig> = OnceLock::new();
+
+pub fn init(config: BotConfig) {
+    CONFIG
+        .set(config)
+        .expect("bot configuration must be initialized only once");
+}
+
+pub fn get() -> &'static BotConfig {
+    CONFIG
+        .get()
+        .expect("bot configuration must be initialized at startup")
+}
@@ -0,0 +1,285 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use teloxide::{
+    dispatching::dialogue::{Dialogue, Storage},
+    types::{ChatId, VideoNote},
+    RequestError,
+};
+
+use cheer_up_core::{client, dialogue::NewDialogue};
+
+use crate::callbacks::{Payload, QueryData, Topic};
+
+pub type ManagerDialogue = Dialogue<State, ApiStorage>;
+
+/// A video note sent to the bot, saved as a note once its author confirms it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteDraft {
+    pub vnote: VideoNote,
+    // INFO: `None` is a note for everyone
+    pub recipient_id: Option<i64>,
+    pub caption: Option<String>,
+}
+
+impl NoteDraft {
+    pub fn new(vnote: VideoNote) -> NoteDraft {
+        NoteDraft {
+            vnote,
+            recipient_id: None,
+            caption: None,
+        }
+    }
+}
+
+/// Step of a chat in the multi-step flows of the bot. Uploading goes through
+/// `PickingRecipient`, `WritingCaption` and `ConfirmingNote`, deleting notes
+/// through a single confirmation, or `SelectingNotes` on the `/list` pages
+/// first. Typed commands and buttons of other pages leave the flow.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum State {
+    #[default]
+    Idle,
+    PickingRecipient {
+        draft: NoteDraft,
+    },
+    WritingCaption {
+        draft: NoteDraft,
+    },
+    ConfirmingNote {
+        draft: NoteDraft,
+    },
+    ConfirmingDelete {
+        note_id: i64,
+    },
+    ConfirmingEraseAll,
+    SelectingNotes {
+        selected: Vec<i64>,
+        // INFO: cursor of the `/list` page shown, toggling a note shows it again
+        page: Option<Payload>,
+    },
+    ConfirmingBulkDelete {
+        selected: Vec<i64>,
+    },
+}
+
+impl State {
+    /// Whether a button belongs to this step, buttons of other steps (i.e. the
+    /// CONFIRM of a note already saved) are rejected instead of replayed.
+    pub fn accepts(&self, data: &QueryData) -> bool {
+        match data.topic {
+            Topic::PickRecipient => matches!(self, State::PickingRecipient { .. }),
+            Topic::SkipCaption => matches!(self, State::WritingCaption { .. }),
+            Topic::ConfirmNote => matches!(self, State::ConfirmingNote { .. }),
+            Topic::ConfirmDelete => match self {
+                State::ConfirmingDelete { note_id } => {
+                    data.payload == Some(Payload::NoteId(*note_id))
+                }
+                _ => false,
+            },
+            Topic::ConfirmEraseAllNotes => matches!(self, State::ConfirmingEraseAll),
+            Topic::ToggleNote => matches!(self, State::SelectingNotes { .. }),
+            Topic::DeleteSelectedNotes => match self {
+                State::SelectingNotes { selected, .. } => !selected.is_empty(),
+                _ => false,
+            },
+            Topic::ConfirmBulkDelete => matches!(self, State::ConfirmingBulkDelete { .. }),
+            _ => true,
+        }
+    }
+
+    pub fn is_selecting(&self) -> bool {
+        matches!(
+            self,
+            State::SelectingNotes { .. } | State::ConfirmingBulkDelete { .. }
+        )
+    }
+}
+
+impl Topic {
+    /// Whether the button moves a flow forward, buttons of every other topic leave it.
+    pub fn is_dialogue_step(&self) -> bool {
+        matches!(
+            self,
+            Topic::DeleteNote
+                | Topic::ConfirmDelete
+                | Topic::EraseAllNotes
+                | Topic::ConfirmEraseAllNotes
+                | Topic::PickRecipient
+                | Topic::SkipCaption
+                | Topic::ConfirmNote
+                | Topic::CancelDialogue
+                | Topic::SelectNotes
+                | Topic::ToggleNote
+                | Topic::DeleteSelectedNotes
+                | Topic::ConfirmBulkDelete
+        )
+    }
+}
+
+/// Keeps dialogues in `cheer-up-api`, so that flows survive bot restarts.
+///
+/// States are stored as JSON, states written by an older version of the bot
+/// that cannot be read anymore start the flow over.
+#[derive(Debug, Default)]
+pub struct ApiStorage;
+
+impl ApiStorage {
+    pub fn new() -> Arc<ApiStorage> {
+        Arc::new(ApiStorage)
+    }
+}
+
+impl<D> Storage<D> for ApiStorage
+where
+    D: Serialize + DeserializeOwned + Send + 'static,
+{
+    type Error = RequestError;
+
+    fn remove_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            client::global().delete_dialogue(chat_id.0).await?;
+            Ok(())
+        })
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+        dialogue: D,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let state =
+                serde_json::to_string(&dialogue).map_err(|e| RequestError::InvalidJson {
+                    source: e,
+                    raw: "".into(),
+                })?;
+
+            client::global()
+                .set_dialogue(chat_id.0, &NewDialogue { state })
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn get_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<Option<D>, Self::Error>> {
+        Box::pin(async move {
+            let dialogue = match client::global().get_dialogue(chat_id.0).await {
+                Ok(dialogue) => dialogue,
+                Err(e) if e.is_not_found() => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+
+            match serde_json::from_str(&dialogue.state) {
+                Ok(state) => Ok(Some(state)),
+                Err(e) => {
+                    warn!(
+                        "[API_STORAGE] unreadable state of chat {}, starting over: {}",
+                        chat_id, e
+                    );
+                    Ok(None)
+                }
+            }
+        })
+    }
+}
+
+/// Leaves the current flow, if any.
+pub async fn exit_dialogue(dialogue: &ManagerDialogue, state: &State) -> Result<(), RequestError> {
+    if *state != State::Idle {
+        info!(
+            "[EXIT_DIALOGUE] chat {} leaves {:?}",
+            dialogue.chat_id(),
+            state
+        );
+        dialogue.exit().await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use teloxide::types::FileMeta;
+
+    fn draft() -> NoteDraft {
+        NoteDraft::new(VideoNote {
+            file: FileMeta {
+                id: "file-id".to_string(),
+                unique_id: "unique-id".to_string(),
+                size: 1024,
+            },
+            length: 240,
+            duration: 5,
+            thumb: None,
+        })
+    }
+
+    fn button(topic: Topic, payload: Option<Payload>) -> QueryData {
+        QueryData { topic, payload }
+    }
+
+    #[test]
+    fn steps_only_accept_their_buttons() {
+        let confirm_note = button(Topic::ConfirmNote, None);
+        assert!(State::ConfirmingNote { draft: draft() }.accepts(&confirm_note));
+        assert!(!State::Idle.accepts(&confirm_note));
+        assert!(!State::WritingCaption { draft: draft() }.accepts(&confirm_note));
+
+        let confirm_delete = button(Topic::ConfirmDelete, Some(Payload::NoteId(7)));
+        assert!(State::ConfirmingDelete { note_id: 7 }.accepts(&confirm_delete));
+        assert!(!State::ConfirmingDelete { note_id: 8 }.accepts(&confirm_delete));
+        assert!(!State::ConfirmingEraseAll.accepts(&confirm_delete));
+
+        // INFO: leaving a flow is always allowed
+        assert!(
+            State::PickingRecipient { draft: draft() }.accepts(&button(Topic::GoHomePage, None))
+        );
+        assert!(State::Idle.accepts(&button(Topic::CancelDialogue, None)));
+    }
+
+    #[test]
+    fn bulk_delete_needs_a_selection() {
+        let delete_selected = button(Topic::DeleteSelectedNotes, None);
+        let selecting = |selected: Vec<i64>| State::SelectingNotes {
+            selected,
+            page: None,
+        };
+
+        assert!(!selecting(vec![]).accepts(&delete_selected));
+        assert!(selecting(vec![4, 2]).accepts(&delete_selected));
+        assert!(!State::Idle.accepts(&delete_selected));
+
+        let toggle = button(Topic::ToggleNote, Some(Payload::NoteId(4)));
+        assert!(selecting(vec![]).accepts(&toggle));
+        assert!(!State::ConfirmingBulkDelete { selected: vec![4] }.accepts(&toggle));
+
+        let confirm = button(Topic::ConfirmBulkDelete, None);
+        assert!(State::ConfirmingBulkDelete { selected: vec![4] }.accepts(&confirm));
+        assert!(!selecting(vec![4]).accepts(&confirm));
+    }
+
+    #[test]
+    fn states_round_trip_through_json() {
+        let state = State::ConfirmingNote {
+            draft: NoteDraft {
+                recipient_id: Some(3),
+                caption: Some("<b>hi</b> 👋".to_string()),
+                ..draft()
+            },
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use log::{info, warn};
+use teloxide::{prelude::*, types::ParseMode};
+
+use cheer_up_core::client;
+
+pub use cheer_up_core::digest::{Digest, DigestQuery, DigestSettings};
+
+use crate::config;
+use crate::templates::Templates;
+
+/// How often due digests are looked up, each author still gets at most one
+/// every `digest_period_days`.
+pub const DIGEST_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub async fn get_digest_settings(user_id: &i64) -> ResponseResult<DigestSettings> {
+    let settings = client::global().get_digest_settings(*user_id).await?;
+
+    Ok(settings)
+}
+
+pub async fn set_digest_muted(user_id: &i64, muted: bool) -> ResponseResult<DigestSettings> {
+    let settings = client::global().set_digest_muted(*user_id, muted).await?;
+
+    Ok(settings)
+}
+
+/// Sends due digests until the bot stops, spawned next to the dispatcher.
+pub async fn run(bot: Bot) {
+    let mut interval = tokio::time::interval(DIGEST_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = send_due_digests(&bot).await {
+            warn!("[DIGESTS] could not send digests: {}", e);
+        }
+    }
+}
+
+async fn send_due_digests(bot: &Bot) -> ResponseResult<()> {
+    let query = DigestQuery {
+        period_days: config::get().digest_period_days,
+    };
+
+    let digests = client::global().list_due_digests(&query).await?;
+    info!("[DIGESTS] {} digests due", digests.len());
+
+    for digest in digests {
+        // INFO: nobody watched the notes, stay quiet and just start a new period
+        if !digest.is_empty() {
+            let locale_str = digest.locale.to_string();
+            let template = Templates::DigestPage(digest.clone(), query.period_days);
+
+            // INFO: failed digests (i.e. the author blocked the bot) are not retried
+            if let Err(e) = bot
+                .send_message(ChatId(digest.telegram_id), template.render(&locale_str))
+                .parse_mode(ParseMode::Html)
+                .await
+            {
+                warn!(
+                    "[DIGESTS] could not send digest to user {}: {}",
+                    digest.user_id, e
+                );
+            }
+        }
+
+        client::global().mark_digest_sent(&digest).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use teloxide::requests::ResponseResult;
+
+use cheer_up_core::client;
+
+use crate::user::User;
+
+pub use cheer_up_core::group::{Group, JoinGroup, NewGroup};
+
+pub async fn get_user_groups(user_id: &i64) -> ResponseResult<Vec<Group>> {
+    let groups = client::global().list_user_groups(*user_id).await?;
+
+    Ok(groups)
+}
+
+pub async fn create_group(name: &str, user_id: &i64) -> ResponseResult<Group> {
+    let new_group = NewGroup {
+        name: name.to_string(),
+        created_by: *user_id,
+    };
+    let group = client::global().create_group(&new_group).await?;
+
+    Ok(group)
+}
+
+/// Friends sharing at least one group with the user.
+pub async fn get_user_peers(user_id: &i64) -> ResponseResult<Vec<User>> {
+    let peers = client::global().list_user_peers(*user_id).await?;
+
+    Ok(peers)
+}
+
+/// Adds the user to the group with `invite_code`, `None` if the code is unknown.
+pub async fn join_group(invite_code: &str, user_id: &i64) -> ResponseResult<Option<Group>> {
+    let join = JoinGroup {
+        user_id: *user_id,
+        invite_code: invite_code.to_string(),
+    };
+
+    match client::global().join_group(&join).await {
+        Ok(group) => Ok(Some(group)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -1,13 +1,19 @@
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup};
 
 use crate::buttons::{
-    confirm_delete_button, confirm_erase_all_notes_button, delete_note_button,
-    erase_all_notes_button, go_to_credits_button, go_to_help_button, go_to_home_button,
-    go_to_language_button, go_to_upload_button, list_all_notes_button, set_language_EN_button,
-    set_language_ES_button, set_language_IT_button, set_language_UA_button,
+    cancel_dialogue_button, confirm_bulk_delete_button, confirm_delete_button,
+    confirm_erase_all_notes_button, confirm_note_button, delete_note_button,
+    delete_selected_notes_button, erase_all_notes_button, everyone_button, go_to_credits_button,
+    go_to_help_button, go_to_home_button, go_to_language_button, go_to_upload_button,
+    list_all_notes_button, next_page_button, pick_recipient_button, prev_page_button,
+    select_notes_button, set_digest_muted_button, set_language_EN_button, set_language_ES_button,
+    set_language_IT_button, set_language_UA_button, show_note_button, skip_caption_button,
+    toggle_note_button,
 };
-use crate::callbacks::{Payload, QueryData};
+use crate::callbacks::{Payload, Topic};
 use crate::locale::Locale;
+use crate::user::User;
+use crate::videonotes::{Note, NotePage};
 
 pub fn start_page(locale: &Locale) -> InlineKeyboardMarkup {
     let row_1 = vec![
@@ -22,23 +28,112 @@ pub fn start_page(locale: &Locale) -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(keyboard_buttons)
 }
 
+/// `page` is the cursor `vnote_page` was fetched with, selecting notes starts on it.
 pub fn list_notes_page(
+    vnote_page: &NotePage,
+    page: Option<Payload>,
     erase_all_notes_payload: Option<Payload>,
     go_help_payload: Option<Payload>,
     locale: &Locale,
 ) -> InlineKeyboardMarkup {
-    let row_1 = vec![
+    let mut keyboard_buttons =
+        vnote_page_buttons(vnote_page, Topic::ListAllNotes, locale, |vnote| {
+            show_note_button(vnote, locale)
+        });
+
+    if !vnote_page.notes.is_empty() {
+        keyboard_buttons.push(vec![select_notes_button(page, locale)]);
+    }
+
+    keyboard_buttons.push(vec![
         erase_all_notes_button(erase_all_notes_payload.clone(), locale),
         go_to_help_button(go_help_payload.clone(), locale),
-    ];
+    ]);
 
-    let row_2 = vec![go_to_home_button(None, locale)];
+    keyboard_buttons.push(vec![go_to_home_button(None, locale)]);
 
-    let keyboard_buttons = vec![row_1, row_2];
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+pub fn moderate_page(vnote_page: &NotePage, locale: &Locale) -> InlineKeyboardMarkup {
+    let mut keyboard_buttons =
+        vnote_page_buttons(vnote_page, Topic::ModerateNotes, locale, |vnote| {
+            show_note_button(vnote, locale)
+        });
+
+    keyboard_buttons.push(vec![go_to_home_button(None, locale)]);
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+/// Checkbox of each note of the page, `selected` ones are checked. The delete
+/// button is only there once a note is selected.
+pub fn select_notes_page(
+    vnote_page: &NotePage,
+    selected: &[i64],
+    locale: &Locale,
+) -> InlineKeyboardMarkup {
+    let mut keyboard_buttons =
+        vnote_page_buttons(vnote_page, Topic::SelectNotes, locale, |vnote| {
+            toggle_note_button(vnote, selected.contains(&vnote.id), locale)
+        });
+
+    if !selected.is_empty() {
+        keyboard_buttons.push(vec![delete_selected_notes_button(selected.len(), locale)]);
+    }
+
+    keyboard_buttons.push(vec![cancel_dialogue_button(locale)]);
 
     InlineKeyboardMarkup::new(keyboard_buttons)
 }
 
+pub fn bulk_delete_page(locale: &Locale) -> InlineKeyboardMarkup {
+    let row_1 = vec![
+        confirm_bulk_delete_button(locale),
+        cancel_dialogue_button(locale),
+    ];
+
+    let keyboard_buttons = vec![row_1];
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+/// One `entry` button per note, i.e. tapping it sends the note below the list,
+/// then the prev/next buttons turning the page of `page_topic`.
+fn vnote_page_buttons(
+    vnote_page: &NotePage,
+    page_topic: Topic,
+    locale: &Locale,
+    entry: impl Fn(&Note) -> InlineKeyboardButton,
+) -> Vec<Vec<InlineKeyboardButton>> {
+    let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = vnote_page
+        .notes
+        .iter()
+        .map(|vnote| vec![entry(vnote)])
+        .collect();
+
+    let mut page_row = vec![];
+    if let Some(cursor) = vnote_page.prev_cursor {
+        page_row.push(prev_page_button(
+            page_topic.clone(),
+            Some(Payload::PageBefore(cursor)),
+            locale,
+        ));
+    }
+    if let Some(cursor) = vnote_page.next_cursor {
+        page_row.push(next_page_button(
+            page_topic,
+            Some(Payload::PageAfter(cursor)),
+            locale,
+        ));
+    }
+    if !page_row.is_empty() {
+        keyboard_buttons.push(page_row);
+    }
+
+    keyboard_buttons
+}
+
 pub fn erase_all_notes_page(
     confirm_delete_payload: Option<Payload>,
     locale: &Locale,
@@ -108,6 +203,47 @@ pub fn upload_page(
 }
 
 pub fn upload_result_page(locale: &Locale) -> InlineKeyboardMarkup {
+    let keyboard_buttons = vec![vec![go_to_home_button(None, locale)]];
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+// INFO: first step of the upload flow, friends first then EVERYONE
+pub fn pick_recipient_page(peers: &[User], locale: &Locale) -> InlineKeyboardMarkup {
+    let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = peers
+        .iter()
+        .map(|peer| {
+            vec![pick_recipient_button(
+                &peer.username,
+                Some(Payload::UserId(peer.id)),
+            )]
+        })
+        .collect();
+
+    keyboard_buttons.push(vec![everyone_button(locale)]);
+
+    keyboard_buttons.push(vec![cancel_dialogue_button(locale)]);
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+pub fn write_caption_page(locale: &Locale) -> InlineKeyboardMarkup {
+    let row_1 = vec![skip_caption_button(locale), cancel_dialogue_button(locale)];
+
+    let keyboard_buttons = vec![row_1];
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+pub fn confirm_note_page(locale: &Locale) -> InlineKeyboardMarkup {
+    let row_1 = vec![confirm_note_button(locale), cancel_dialogue_button(locale)];
+
+    let keyboard_buttons = vec![row_1];
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+pub fn group_page(locale: &Locale) -> InlineKeyboardMarkup {
     let row_1 = vec![go_to_home_button(None, locale)];
 
     let keyboard_buttons = vec![row_1];
@@ -115,6 +251,17 @@ pub fn upload_result_page(locale: &Locale) -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(keyboard_buttons)
 }
 
+// INFO: a single button switching the current digest setting
+pub fn notifications_page(muted: bool, locale: &Locale) -> InlineKeyboardMarkup {
+    let row_1 = vec![set_digest_muted_button(!muted, locale)];
+
+    let row_2 = vec![go_to_home_button(None, locale)];
+
+    let keyboard_buttons = vec![row_1, row_2];
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
 pub fn credits_page(
     go_language_payload: Option<Payload>,
     go_help_payload: Option<Payload>,
@@ -1,103 +1,38 @@
 use log::debug;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use teloxide::{requests::ResponseResult, types::Chat};
+use teloxide::requests::ResponseResult;
 
-use std::fmt;
+use cheer_up_core::client;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct LocaleBody<T> {
-    locale: T,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum Locale {
-    #[serde(rename = "en")]
-    EN,
-    #[serde(rename = "es")]
-    ES,
-    #[serde(rename = "it")]
-    IT,
-    #[serde(rename = "ua")]
-    UA,
-}
+pub use cheer_up_core::locale::Locale;
 
-impl fmt::Display for Locale {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Locale::EN => write!(f, "en"),
-            Locale::ES => write!(f, "es"),
-            Locale::IT => write!(f, "it"),
-            Locale::UA => write!(f, "ua"),
-        }
-    }
-}
+pub async fn get_user_locale_by_user_id(user_id: &i64) -> ResponseResult<Locale> {
+    let locale = client::global().get_user_locale(*user_id).await?;
 
-impl Locale {
-    pub fn from_str(locale: &str) -> Locale {
-        match locale {
-            "en" => Locale::EN,
-            "es" => Locale::ES,
-            "it" => Locale::IT,
-            "ua" => Locale::UA,
-            _ => Locale::EN,
-        }
-    }
-}
+    debug!("user {:#?} locale is: {:#?}", user_id, locale);
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-struct UserLocale {
-    language: String,
+    Ok(locale)
 }
 
-impl fmt::Display for UserLocale {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", serde_json::to_string(self).unwrap())
-    }
-}
-impl UserLocale {
-    pub fn locale(&self) -> Locale {
-        match self.language.as_str() {
-            "en" => Locale::EN,
-            "es" => Locale::ES,
-            "it" => Locale::IT,
-            "ua" => Locale::UA,
-            _ => Locale::EN,
-        }
-    }
-}
-
-pub async fn get_user_locale_by_user_id(user_id: &i64) -> ResponseResult<Locale> {
-    let client = Client::new();
-
-    let resp = client
-        .get(format!("http://0.0.0.0:1989/api/locale/{}", user_id))
-        .send()
-        .await?
-        .json::<LocaleBody<Locale>>()
-        .await?;
+pub async fn set_user_locale_by_user_id(user_id: &i64, locale: &Locale) -> ResponseResult<Locale> {
+    let locale = client::global().set_user_locale(*user_id, locale).await?;
 
-    debug!("user {:#?} locale is: {:#?}", user_id, resp);
+    debug!("user {:#?} locale is: {:#?}", user_id, locale);
 
-    Ok(resp.locale)
+    Ok(locale)
 }
 
-pub async fn set_user_locale_by_user_id(user_id: &i64, locale: &Locale) -> ResponseResult<Locale> {
-    let new_locale = LocaleBody {
-        locale: locale.clone(),
-    };
-
-    let client = Client::new();
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
 
-    let resp = client
-        .patch(format!("http://0.0.0.0:1989/api/locale/{}", user_id))
-        .json::<LocaleBody<Locale>>(&new_locale)
-        .send()
-        .await?
-        .json::<LocaleBody<Locale>>()
-        .await?;
+    use cheer_up_core::translations::check_translations;
 
-    debug!("user {:#?} locale is: {:#?}", user_id, resp);
+    #[test]
+    fn every_locale_translates_every_key_used() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let issues = check_translations(&root.join("locales"), &root.join("src")).unwrap();
 
-    Ok(resp.locale)
+        let report: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+        assert!(issues.is_empty(), "\n{}", report.join("\n"));
+    }
 }
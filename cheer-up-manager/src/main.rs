@@ -5,35 +5,78 @@ rust_i18n::i18n!("locales", fallback = "en");
 use rust_i18n::set_locale;
 
 // use log;
-use teloxide::{prelude::*, RequestError};
+use cheer_up_core::{
+    callback::{self, CallbackCodec},
+    client, media, CheerUpApiClient,
+};
+use clap::Parser;
+use teloxide::{dispatching::dialogue, prelude::*, RequestError};
+
+use crate::config::{BotArgs, BotConfig};
+use crate::dialogues::{ApiStorage, State};
 
 mod buttons;
 mod callbacks;
 mod commands;
+mod config;
+mod dialogues;
+mod digests;
+mod groups;
 mod keyboards;
 mod locale;
 mod messages;
+mod nav;
 mod stats;
 mod templates;
 mod user;
 mod utils;
 mod videonotes;
 
+#[derive(Debug, Parser)]
+#[command(about = "CheerUp manager bot, used to upload and handle video notes")]
+struct Cli {
+    #[command(flatten)]
+    bot: BotArgs,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), RequestError> {
     pretty_env_logger::init();
     dotenvy::dotenv().ok();
 
-    let app_locale = dotenvy::var("LOCALE").unwrap_or("en".to_string());
-    set_locale(&app_locale);
+    // INFO: validate configuration once, before the bot starts answering users
+    let cli = Cli::parse();
+    let config = BotConfig::load(cli.bot).unwrap_or_else(|e| {
+        eprintln!("invalid configuration: {}", e);
+        std::process::exit(1);
+    });
+
+    set_locale(config.default_locale.code());
+
+    client::init(CheerUpApiClient::new(&config.api_url).with_api_key(&config.api_key));
+    callback::init(CallbackCodec::new(config.callback_secret.as_bytes()));
+    media::init(config.media.build());
+
+    let bot = Bot::new(&config.telegram_token);
+    config::init(config);
 
-    let bot = Bot::from_env();
+    tokio::spawn(digests::run(bot.clone()));
 
     // teloxide::repl(bot, handle_input).await;
+    // INFO: every update gets the dialogue of its chat, see `dialogues::State`
     let handler = dptree::entry()
-        .branch(Update::filter_message().endpoint(messages::handle_message))
-        .branch(Update::filter_callback_query().endpoint(callbacks::handle_callback));
+        .branch(
+            Update::filter_message()
+                .chain(dialogue::enter::<Update, ApiStorage, State, _>())
+                .endpoint(messages::handle_message),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .chain(dialogue::enter::<Update, ApiStorage, State, _>())
+                .endpoint(callbacks::handle_callback),
+        );
     Dispatcher::builder(bot, handler)
+        .dependencies(dptree::deps![ApiStorage::new()])
         .enable_ctrlc_handler()
         .build()
         .dispatch()
@@ -1,13 +1,13 @@
 use log::{debug, info};
 use teloxide::{
     prelude::*,
-    types::{Me, ParseMode},
+    types::{ChatAction, Me, ParseMode},
 };
 
+use crate::dialogues::{exit_dialogue, ManagerDialogue, State};
+use crate::templates::*;
 use crate::user::*;
-use crate::videonotes::*;
 use crate::{commands::*, locale::get_user_locale_by_user_id};
-use crate::{keyboards, templates::*};
 
 #[derive(Debug, Clone, Copy)]
 pub enum MessageType {
@@ -68,20 +68,22 @@ impl MessageType {
     }
 }
 
-pub async fn handle_message(bot: Bot, msg: Message, me: Me) -> ResponseResult<()> {
-    let user = get_user(&msg.chat).await?;
+pub async fn handle_message(
+    bot: Bot,
+    msg: Message,
+    _me: Me,
+    dialogue: ManagerDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    // INFO: every conversation starts with a message, this is where users get registered
+    let user = match msg.from() {
+        Some(from) => register_user(from).await?,
+        None => get_user(&msg.chat).await?,
+    };
     info!("[HANDLE_MESSAGE] user is: {:?}", user);
 
-    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
-    info!("[HANDLE_MESSAGE] remote_locale is: {:?}", remote_locale);
-
-    let locale_str = remote_locale.to_string();
-
     // INFO: show loading indicator
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
+    bot.send_chat_action(msg.chat.id, ChatAction::Typing)
         .await?;
 
     // INFO: parse message to intercept videonotes uploadings
@@ -95,44 +97,8 @@ pub async fn handle_message(bot: Bot, msg: Message, me: Me) -> ResponseResult<()
             info!("[HANDLE_MESSAGE] received video note");
             let vnote = msg.video_note().unwrap();
 
-            // INFO: save user to db
-            let save_user = save_user_to_db(&msg.chat).await;
-            if save_user.is_err() {
-                info!("[HANDLE_MESSAGE] an error occurred while saving user to db");
-            }
-
-            // INFO: upload vnote to server disk
-            let upload_result = upload_vnote(&bot, vnote, &msg.chat).await;
-            if upload_result.is_err() {
-                info!("[HANDLE_MESSAGE] an error occurred while saving note");
-                let keyboard = keyboards::upload_result_page(&remote_locale);
-                bot.send_message(msg.chat.id, Templates::ErrorUploadPage.render(&locale_str))
-                    .parse_mode(ParseMode::Html)
-                    .reply_markup(keyboard)
-                    .await?;
-                return Ok(());
-            }
-
-            // INFO: save vnote to db
-            let save_vnote = save_vnote_to_db(&vnote.file.id, &msg.chat).await;
-            if save_vnote.is_err() {
-                info!("[HANDLE_MESSAGE] an error occurred while saving note");
-                let keyboard = keyboards::upload_result_page(&remote_locale);
-                bot.send_message(msg.chat.id, Templates::ErrorUploadPage.render(&locale_str))
-                    .parse_mode(ParseMode::Html)
-                    .reply_markup(keyboard)
-                    .await?;
-                return Ok(());
-            }
-
-            let template = Templates::SuccessUploadPage;
-            let keyboard = keyboards::upload_result_page(&remote_locale);
-
-            // bot.send_message(msg.chat.id, template.render())
-            bot.send_message(msg.chat.id, template.render(&locale_str))
-                .parse_mode(ParseMode::Html)
-                .reply_markup(keyboard)
-                .await?;
+            // INFO: the note is saved once its author confirms recipient and caption
+            receive_vnote_command(&bot, msg.clone(), vnote, &dialogue).await?;
             Ok(())
         }
         MessageType::Text => {
@@ -145,11 +111,20 @@ pub async fn handle_message(bot: Bot, msg: Message, me: Me) -> ResponseResult<()
 
             if let Some(cmd) = command {
                 info!("[HANDLE_MESSAGE] Command you sent: {:?}", cmd);
+                // INFO: typed commands leave the current flow
+                exit_dialogue(&dialogue, &state).await?;
                 handle_commands(bot.clone(), cmd, msg.clone()).await?;
 
                 return Ok(());
             }
 
+            if let State::WritingCaption { draft } = state {
+                caption_command(&bot, msg.clone(), &dialogue, draft).await?;
+
+                return Ok(());
+            }
+
+            exit_dialogue(&dialogue, &state).await?;
             start_command(&bot, msg.clone()).await?;
 
             Ok(())
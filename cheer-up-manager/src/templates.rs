@@ -1,20 +1,25 @@
-use dotenvy as dotenv;
 use log::debug;
+use teloxide::utils::html;
 
+use crate::config;
+use crate::digests::Digest;
+use crate::groups::Group;
 use crate::stats::UserStats;
+use crate::videonotes::NoteReactions;
 
 #[derive(Debug, Clone)]
 pub enum Templates {
-    LoadingPage,
     StartPage(String),
     RandomNotePage(String),
     DeleteNotePage(String),
     SuccessDeleteNotePage(String),
     ErrorDeleteNotePage,
+    NotNoteOwnerPage,
     UploadPage(String, String, String, Vec<UserStats>),
     SuccessUploadPage,
     ErrorUploadPage,
-    ListPage(String, String),
+    // INFO: (user, total notes, page number, page count)
+    ListPage(String, String, usize, usize),
     EraseAllNotesPage(String),
     SuccessEraseAllNotesPage(String),
     ErrorEraseAllNotesPage,
@@ -22,47 +27,93 @@ pub enum Templates {
     HelpPage,
     CreditsPage,
     UnsupportedInputPage(String),
+    InvitePage(Vec<Group>),
+    JoinGroupPage(String),
+    ErrorJoinGroupPage,
+    NoteReactionsEntry(NoteReactions),
+    NotificationsPage(bool, u32),
+    DigestPage(Digest, u32),
+    // INFO: (total notes, page number, page count)
+    ModeratePage(String, usize, usize),
+    NoteUnavailablePage,
+    NotAdminPage,
+    InvalidButtonPage,
+    StaleButtonAlert,
+    PickRecipientPage,
+    // INFO: (caption length limit)
+    WriteCaptionPage(usize),
+    CaptionTooLongPage(usize),
+    // INFO: (recipient username, `None` for everyone, caption)
+    ConfirmNotePage(Option<String>, Option<String>),
+    OutOfOrderButtonAlert,
+    // INFO: (selected notes, page number, page count)
+    SelectNotesPage(usize, usize, usize),
+    BulkDeletePage(Vec<i64>),
+    SuccessBulkDeletePage(usize),
 }
 
 impl Templates {
     pub fn render(&self, locale: &str) -> String {
         debug!("rendering with locale: {:?}", locale);
-        let author =
-            dotenv::var("AUTHOR").expect("error loading author from envirenment variables");
-
-        let profile_name = dotenv::var("AUTHOR_PROFILE_NAME")
-            .expect("error loading author from envirenment variables");
-
-        let profile_url = dotenv::var("AUTHOR_PROFILE_URL")
-            .expect("error loading author profile url from envirenment variables");
-
-        let repo_url = dotenv::var("CODE_REPO_URL")
-            .expect("error loading code repo url from envirenment variables");
+        let credits = &config::get().credits;
 
         match self {
-            Templates::LoadingPage => loading_page(locale),
             Templates::StartPage(user) => start_page(user, locale),
             Templates::RandomNotePage(user) => random_note_page(user, locale),
             Templates::DeleteNotePage(note_id) => delete_note_page(note_id, locale),
             Templates::SuccessDeleteNotePage(note_id) => success_delete_note_page(note_id, locale),
             Templates::ErrorDeleteNotePage => error_delete_note_page(locale),
+            Templates::NotNoteOwnerPage => not_note_owner_page(locale),
             Templates::UploadPage(user, total_notes, total_users, user_videonotes_list) => {
                 upload_page(user, total_notes, total_users, user_videonotes_list, locale)
             }
             Templates::SuccessUploadPage => success_upload_page(locale),
             Templates::ErrorUploadPage => error_upload_page(locale),
-            Templates::ListPage(user, total_notes) => list_page(user, total_notes, locale),
+            Templates::ListPage(user, total_notes, page, pages) => {
+                list_page(user, total_notes, *page, *pages, locale)
+            }
             Templates::EraseAllNotesPage(user_id) => erase_all_notes_page(user_id, locale),
             Templates::SuccessEraseAllNotesPage(user_id) => {
                 success_erase_all_notes_page(user_id, locale)
             }
             Templates::ErrorEraseAllNotesPage => error_erase_all_notes_page(locale),
-            Templates::LanguagePage => language_page(&repo_url, locale),
+            Templates::LanguagePage => language_page(&credits.repo_url, locale),
             Templates::HelpPage => help_page(locale),
-            Templates::CreditsPage => {
-                credits_page(&author, &profile_name, &profile_url, &repo_url, locale)
-            }
+            Templates::CreditsPage => credits_page(
+                &credits.author,
+                &credits.profile_name,
+                &credits.profile_url,
+                &credits.repo_url,
+                locale,
+            ),
             Templates::UnsupportedInputPage(input) => unsupported_input_page(input, locale),
+            Templates::InvitePage(groups) => invite_page(groups, locale),
+            Templates::JoinGroupPage(group) => join_group_page(group, locale),
+            Templates::ErrorJoinGroupPage => error_join_group_page(locale),
+            Templates::NoteReactionsEntry(reactions) => note_reactions_entry(reactions, locale),
+            Templates::NotificationsPage(muted, period_days) => {
+                notifications_page(muted, period_days, locale)
+            }
+            Templates::DigestPage(digest, period_days) => digest_page(digest, period_days, locale),
+            Templates::ModeratePage(total_notes, page, pages) => {
+                moderate_page(total_notes, *page, *pages, locale)
+            }
+            Templates::NoteUnavailablePage => note_unavailable_page(locale),
+            Templates::NotAdminPage => not_admin_page(locale),
+            Templates::InvalidButtonPage => invalid_button_page(locale),
+            Templates::StaleButtonAlert => stale_button_alert(locale),
+            Templates::PickRecipientPage => pick_recipient_page(locale),
+            Templates::WriteCaptionPage(max) => write_caption_page(*max, locale),
+            Templates::CaptionTooLongPage(max) => caption_too_long_page(*max, locale),
+            Templates::ConfirmNotePage(recipient, caption) => {
+                confirm_note_page(recipient.as_deref(), caption.as_deref(), locale)
+            }
+            Templates::OutOfOrderButtonAlert => out_of_order_button_alert(locale),
+            Templates::SelectNotesPage(selected, page, pages) => {
+                select_notes_page(*selected, *page, *pages, locale)
+            }
+            Templates::BulkDeletePage(note_ids) => bulk_delete_page(note_ids, locale),
+            Templates::SuccessBulkDeletePage(count) => success_bulk_delete_page(*count, locale),
         }
     }
 }
@@ -98,8 +149,8 @@ fn error_delete_note_page(locale: &str) -> String {
     format!("{}", t!("error_delete_note_page", locale = locale))
 }
 
-fn loading_page(locale: &str) -> String {
-    format!("{}", t!("loading_page", locale = locale))
+fn not_note_owner_page(locale: &str) -> String {
+    format!("{}", t!("not_note_owner_page", locale = locale))
 }
 
 fn upload_page(
@@ -147,18 +198,35 @@ fn error_upload_page(locale: &str) -> String {
     format!("{}", t!("error_upload_page", locale = locale))
 }
 
-fn list_page(user: &str, total_notes: &str, locale: &str) -> String {
+fn list_page(user: &str, total_notes: &str, page: usize, pages: usize, locale: &str) -> String {
     format!(
-        "{}",
+        "{}{}",
         t!(
             "list_page",
             locale = locale,
             user = user,
             total_notes = total_notes
-        )
+        ),
+        list_page_position(page, pages, locale)
     )
 }
 
+/// Page line appended to `/list` and `/moderate`, empty when everything fits in one page.
+fn list_page_position(page: usize, pages: usize, locale: &str) -> String {
+    match pages {
+        0 | 1 => "".to_string(),
+        pages => format!(
+            "\n{}",
+            t!(
+                "list_page_position",
+                locale = locale,
+                page = page,
+                pages = pages
+            )
+        ),
+    }
+}
+
 fn erase_all_notes_page(user_id: &str, locale: &str) -> String {
     format!(
         "{}",
@@ -246,3 +314,174 @@ fn unsupported_input_page(input_type: &str, locale: &str) -> String {
 
     format!("{}", t!("unsupported_page.other_media", locale = locale))
 }
+
+fn invite_page(groups: &Vec<Group>, locale: &str) -> String {
+    let mut groups_list = String::new();
+
+    for group in groups {
+        let new_group_entry = format!(
+            "{}{}",
+            groups_list,
+            t!(
+                "invite_page_group_entry",
+                locale = locale,
+                group = group.name,
+                invite_code = group.invite_code,
+            )
+        );
+
+        groups_list = new_group_entry;
+    }
+
+    format!(
+        "{}",
+        t!("invite_page", locale = locale, groups_list = groups_list)
+    )
+}
+
+fn join_group_page(group: &str, locale: &str) -> String {
+    format!("{}", t!("join_group_page", locale = locale, group = group))
+}
+
+fn error_join_group_page(locale: &str) -> String {
+    format!("{}", t!("error_join_group_page", locale = locale))
+}
+
+fn note_reactions_entry(reactions: &NoteReactions, locale: &str) -> String {
+    format!(
+        "{}",
+        t!(
+            "note_reactions_entry",
+            locale = locale,
+            heart = reactions.heart,
+            laugh = reactions.laugh,
+            cry = reactions.cry,
+            favorites = reactions.favorites
+        )
+    )
+}
+
+fn notifications_page(muted: &bool, period_days: &u32, locale: &str) -> String {
+    match muted {
+        true => format!("{}", t!("notifications_page.muted", locale = locale)),
+        false => format!(
+            "{}",
+            t!(
+                "notifications_page.unmuted",
+                locale = locale,
+                days = period_days
+            )
+        ),
+    }
+}
+
+fn digest_page(digest: &Digest, period_days: &u32, locale: &str) -> String {
+    format!(
+        "{}",
+        t!(
+            "digest_page",
+            locale = locale,
+            days = period_days,
+            views = digest.views,
+            heart = digest.heart,
+            laugh = digest.laugh,
+            cry = digest.cry
+        )
+    )
+}
+
+fn moderate_page(total_notes: &str, page: usize, pages: usize, locale: &str) -> String {
+    format!(
+        "{}{}",
+        t!("moderate_page", locale = locale, total_notes = total_notes),
+        list_page_position(page, pages, locale)
+    )
+}
+
+fn note_unavailable_page(locale: &str) -> String {
+    format!("{}", t!("note_unavailable_page", locale = locale))
+}
+
+fn not_admin_page(locale: &str) -> String {
+    format!("{}", t!("not_admin_page", locale = locale))
+}
+
+fn invalid_button_page(locale: &str) -> String {
+    format!("{}", t!("invalid_button_page", locale = locale))
+}
+
+// INFO: shown in a callback answer alert, plain text of at most 200 characters
+fn stale_button_alert(locale: &str) -> String {
+    format!("{}", t!("stale_button_alert", locale = locale))
+}
+
+fn pick_recipient_page(locale: &str) -> String {
+    format!("{}", t!("pick_recipient_page", locale = locale))
+}
+
+fn write_caption_page(max: usize, locale: &str) -> String {
+    format!("{}", t!("write_caption_page", locale = locale, max = max))
+}
+
+fn caption_too_long_page(max: usize, locale: &str) -> String {
+    format!(
+        "{}",
+        t!("caption_too_long_page", locale = locale, max = max)
+    )
+}
+
+fn confirm_note_page(recipient: Option<&str>, caption: Option<&str>, locale: &str) -> String {
+    let recipient = match recipient {
+        Some(recipient) => format!("@{}", recipient),
+        None => t!("confirm_note_page.everyone", locale = locale).to_string(),
+    };
+
+    // INFO: captions are typed by users, pages are parsed as HTML
+    let caption = match caption {
+        Some(caption) => html::escape(caption),
+        None => t!("confirm_note_page.no_caption", locale = locale).to_string(),
+    };
+
+    format!(
+        "{}",
+        t!(
+            "confirm_note_page",
+            locale = locale,
+            recipient = recipient,
+            caption = caption
+        )
+    )
+}
+
+// INFO: shown in a callback answer alert, plain text of at most 200 characters
+fn out_of_order_button_alert(locale: &str) -> String {
+    format!("{}", t!("out_of_order_button_alert", locale = locale))
+}
+
+fn select_notes_page(selected: usize, page: usize, pages: usize, locale: &str) -> String {
+    format!(
+        "{}{}",
+        t!("select_notes_page", locale = locale, count = selected),
+        list_page_position(page, pages, locale)
+    )
+}
+
+fn bulk_delete_page(note_ids: &[i64], locale: &str) -> String {
+    let note_ids = note_ids
+        .iter()
+        .map(|note_id| format!("#{}", note_id))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        "{}",
+        t!("bulk_delete_page", locale = locale, note_ids = note_ids)
+    )
+}
+
+fn success_bulk_delete_page(count: usize, locale: &str) -> String {
+    format!(
+        "{}",
+        t!("success_bulk_delete_page", locale = locale, count = count)
+    )
+}
@@ -1,12 +1,19 @@
+use cheer_up_core::callback;
+use log::error;
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup};
 
 use crate::{
     callbacks::{Payload, QueryData, Topic},
     locale::Locale,
+    videonotes::{Note, Reaction},
 };
 
 pub fn make_button(label: &str, query_data: &QueryData) -> InlineKeyboardButton {
-    let data = serde_json::to_string(query_data).unwrap_or("none".to_string());
+    // INFO: signed and at most 64 bytes, long payloads are kept in memory by the codec
+    let data = callback::global().encode(query_data).unwrap_or_else(|e| {
+        error!("[MAKE_BUTTON] cannot encode {:?}: {}", query_data, e);
+        "none".to_string()
+    });
 
     InlineKeyboardButton::new(label, InlineKeyboardButtonKind::CallbackData(data))
 }
@@ -23,6 +30,21 @@ pub fn ask_friend_button(payload: Option<Payload>, locale: &Locale) -> InlineKey
     )
 }
 
+pub fn personal_note_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::GetPersonalNote,
+        payload,
+    };
+
+    make_button(
+        &t!(
+            "buttons.personal_note",
+            locale = locale.to_string().as_str()
+        ),
+        &query_data,
+    )
+}
+
 pub fn list_all_notes_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
     let query_data = QueryData {
         topic: Topic::ListAllNotes,
@@ -35,6 +57,51 @@ pub fn list_all_notes_button(payload: Option<Payload>, locale: &Locale) -> Inlin
     )
 }
 
+pub fn show_note_button(vnote: &Note, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::ShowNote,
+        payload: Some(Payload::NoteId(vnote.id)),
+    };
+
+    let label = t!(
+        "buttons.show_note",
+        locale = locale.to_string().as_str(),
+        note_id = vnote.id
+    );
+
+    // INFO: upload day, `created_at` starts with `YYYY-MM-DD`
+    let label = match vnote.created_at.as_deref().and_then(|date| date.get(..10)) {
+        Some(day) => format!("{} · {}", label, day),
+        None => label.to_string(),
+    };
+
+    make_button(&label, &query_data)
+}
+
+pub fn prev_page_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::ListAllNotes,
+        payload,
+    };
+
+    make_button(
+        &t!("buttons.prev_page", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
+pub fn next_page_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::ListAllNotes,
+        payload,
+    };
+
+    make_button(
+        &t!("buttons.next_page", locale = locale.to_string().as_str()),
+        &query_data,
+    )
+}
+
 pub fn delete_note_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
     let query_data = QueryData {
         topic: Topic::DeleteNote,
@@ -190,3 +257,28 @@ pub fn set_language_IT_button(payload: Option<Payload>, locale: &Locale) -> Inli
         &query_data,
     )
 }
+
+// INFO: labelled with the reaction emoji, no translation needed
+pub fn react_button(note_id: i64, reaction: Reaction) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::React,
+        payload: Some(Payload::NoteReaction(note_id, reaction)),
+    };
+
+    make_button(reaction.emoji(), &query_data)
+}
+
+pub fn save_favorite_button(payload: Option<Payload>, locale: &Locale) -> InlineKeyboardButton {
+    let query_data = QueryData {
+        topic: Topic::SaveFavorite,
+        payload,
+    };
+
+    make_button(
+        &t!(
+            "buttons.save_favorite",
+            locale = locale.to_string().as_str()
+        ),
+        &query_data,
+    )
+}
@@ -6,10 +6,10 @@ use teloxide::{
     Bot, RequestError,
 };
 
+use cheer_up_core::callback;
 use serde::{self, Deserialize, Serialize};
-use serde_json;
 
-use crate::{commands, locale::Locale, user::UserId};
+use crate::{commands, locale::Locale, nav, videonotes::Reaction};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct QueryData {
@@ -23,6 +23,10 @@ pub enum Payload {
     Username(String),
     UserId(i64),
     NoteId(i64),
+    NoteReaction(i64, Reaction),
+    // INFO: cursors of the `/list` page to show, see `NotePage`
+    PageAfter(i64),
+    PageBefore(i64),
 }
 
 impl fmt::Display for Payload {
@@ -32,13 +36,18 @@ impl fmt::Display for Payload {
             Payload::Username(text) => write!(f, "{}", text),
             Payload::UserId(id) => write!(f, "{}", id),
             Payload::NoteId(id) => write!(f, "{}", id),
+            Payload::NoteReaction(id, reaction) => write!(f, "{}:{}", id, reaction.emoji()),
+            Payload::PageAfter(id) => write!(f, ">{}", id),
+            Payload::PageBefore(id) => write!(f, "<{}", id),
         }
     }
 }
 
+// INFO: buttons carry the variant index, new topics go last so sent buttons keep theirs
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum Topic {
     GetRandomNote,
+    GetPersonalNote,
     ListAllNotes,
     DeleteNote,
     ConfirmDelete,
@@ -49,13 +58,18 @@ pub enum Topic {
     GoLanguagePage,
     GoHelpPage,
     SetLanguage,
+    React,
+    SaveFavorite,
+    ShowNote,
 }
 
 impl Topic {
     pub fn name(&self) -> String {
         match self {
             Topic::GetRandomNote => "#random_note".to_string(),
+            Topic::GetPersonalNote => "#personal_note".to_string(),
             Topic::ListAllNotes => "#list".to_string(),
+            Topic::ShowNote => "#show_note".to_string(),
             Topic::DeleteNote => "#delete".to_string(),
             Topic::ConfirmDelete => "#confirm_delete".to_string(),
             Topic::GoHomePage => "#home".to_string(),
@@ -65,14 +79,31 @@ impl Topic {
             Topic::GoLanguagePage => "#language".to_string(),
             Topic::GoHelpPage => "#help".to_string(),
             Topic::SetLanguage => "#set_language".to_string(),
+            Topic::React => "#react".to_string(),
+            Topic::SaveFavorite => "#save_favorite".to_string(),
         }
     }
 }
 
 pub async fn handle_callback(bot: Bot, query: CallbackQuery) -> Result<(), RequestError> {
-    // suppress waiting spinner from button after user taps it
-    bot.answer_callback_query(query.id).await?;
+    let query_id = query.id.clone();
 
+    // INFO: only the latest page of a chat is live, buttons left on older ones are stale
+    if let Some(msg) = query.message.clone().filter(nav::is_stale) {
+        return commands::stale_button_command(&bot, msg, query_id).await;
+    }
+
+    let handled = dispatch_callback(&bot, query).await;
+
+    // INFO: answered once the page is shown, the button spinner is the loading indicator
+    if let Err(e) = bot.answer_callback_query(query_id).await {
+        warn!("[HANDLE_CALLBACK] cannot answer callback query: {}", e);
+    }
+
+    handled
+}
+
+async fn dispatch_callback(bot: &Bot, query: CallbackQuery) -> ResponseResult<()> {
     let message = query.message.clone();
     let chat = match query.message {
         Some(msg) => Some(msg.chat),
@@ -85,8 +116,7 @@ pub async fn handle_callback(bot: Bot, query: CallbackQuery) -> Result<(), Reque
     };
     // println!("callback query data is: {:#?}", raw_data);
 
-    let callback_query_data = serde_json::from_str::<QueryData>(&raw_data);
-    // println!("callback query data is: {:#?}", callback_query_data);
+    let callback_query_data = callback::global().decode::<QueryData>(&raw_data);
 
     match callback_query_data {
         Ok(data) => {
@@ -95,31 +125,37 @@ pub async fn handle_callback(bot: Bot, query: CallbackQuery) -> Result<(), Reque
             // INFO: call handlers based on query topic passing optional payload
             match topic {
                 Topic::GetRandomNote => {
-                    handle_random_note(&bot, message, chat, data.payload).await?
+                    handle_random_note(bot, message, chat, data.payload).await?
+                }
+                Topic::GetPersonalNote => {
+                    handle_personal_note(bot, message, chat, data.payload).await?
                 }
-                Topic::DeleteNote => handle_delete_note(&bot, message, chat, data.payload).await?,
+                Topic::DeleteNote => handle_delete_note(bot, message, chat, data.payload).await?,
                 Topic::ConfirmDelete => {
-                    handle_confirm_delete(&bot, message, chat, data.payload).await?
+                    handle_confirm_delete(bot, message, chat, data.payload).await?
                 }
                 Topic::ListAllNotes => {
-                    handle_list_all_notes(&bot, message, chat, data.payload).await?
+                    handle_list_all_notes(bot, message, chat, data.payload).await?
                 }
-                Topic::GoHomePage => handle_go_home_page(&bot, message, chat, data.payload).await?,
+                Topic::ShowNote => handle_show_note(bot, message, chat, data.payload).await?,
+                Topic::GoHomePage => handle_go_home_page(bot, message, chat, data.payload).await?,
                 Topic::GoExtraPage => {
-                    handle_go_extra_page(&bot, message, chat, data.payload).await?
+                    handle_go_extra_page(bot, message, chat, data.payload).await?
                 }
                 Topic::GoUploadPage => {
-                    handle_go_upload_page(&bot, message, chat, data.payload).await?
+                    handle_go_upload_page(bot, message, chat, data.payload).await?
                 }
                 Topic::GoCreditsPage => {
-                    handle_go_credits_page(&bot, message, chat, data.payload).await?
+                    handle_go_credits_page(bot, message, chat, data.payload).await?
                 }
                 Topic::GoLanguagePage => {
-                    handle_go_language_page(&bot, message, chat, data.payload).await?
+                    handle_go_language_page(bot, message, chat, data.payload).await?
                 }
-                Topic::GoHelpPage => handle_go_help_page(&bot, message, chat, data.payload).await?,
-                Topic::SetLanguage => {
-                    handle_set_language(&bot, message, chat, data.payload).await?
+                Topic::GoHelpPage => handle_go_help_page(bot, message, chat, data.payload).await?,
+                Topic::SetLanguage => handle_set_language(bot, message, chat, data.payload).await?,
+                Topic::React => handle_react(bot, message, chat, data.payload).await?,
+                Topic::SaveFavorite => {
+                    handle_save_favorite(bot, message, chat, data.payload).await?
                 }
                 _ => warn!("unkwnown topic"),
             }
@@ -127,8 +163,15 @@ pub async fn handle_callback(bot: Bot, query: CallbackQuery) -> Result<(), Reque
             Ok(())
         }
         Err(e) => {
-            println!("ERROR WHILE PARSING CALLBACK DATA: {:?}", e);
-            Ok(())
+            warn!(
+                "[HANDLE_CALLBACK] invalid callback data {:?}: {}",
+                raw_data, e
+            );
+
+            match message {
+                Some(msg) => commands::invalid_button_command(bot, msg).await,
+                None => Ok(()),
+            }
         }
     }
 }
@@ -158,20 +201,20 @@ async fn handle_random_note(
 
                         // INFO: we can safely unwrap msg, since chat is extracted from query.message
                         // iteslf
-                        commands::random_note_command(bot, msg.unwrap()).await?;
+                        commands::random_note_command(bot, msg.unwrap(), false).await?;
                         Ok(())
                     }
                     // RandomNote callback needs Payload::Text only
                     _ => {
                         warn!("payload provided is not Payload::Text");
-                        commands::random_note_command(bot, msg.unwrap()).await?;
+                        commands::random_note_command(bot, msg.unwrap(), false).await?;
                         Ok(())
                     }
                 },
                 None => {
                     // no Payload provided
                     warn!("no Payload provided");
-                    commands::random_note_command(bot, msg.unwrap()).await?;
+                    commands::random_note_command(bot, msg.unwrap(), false).await?;
                     Ok(())
                 }
             }
@@ -184,6 +227,29 @@ async fn handle_random_note(
     }
 }
 
+async fn handle_personal_note(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+) -> ResponseResult<()> {
+    if payload.is_some() {
+        warn!("Payload provided, but not needed");
+    }
+
+    match target {
+        Some(_) => {
+            commands::random_note_command(bot, msg.unwrap(), true).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
 async fn handle_delete_note(
     bot: &Bot,
     msg: Option<teloxide::types::Message>,
@@ -252,8 +318,9 @@ async fn handle_list_all_notes(
         Some(chat) => {
             match payload {
                 Some(data) => {
-                    warn!("Payload provided, but not needed");
-                    commands::list_command(bot, msg.unwrap()).await?;
+                    // INFO: prev/next buttons turn the page of the list they are below
+                    info!("Payload data is: {:?}", data);
+                    commands::list_page_command(bot, msg.unwrap(), Some(data)).await?;
                     Ok(())
                 }
                 None => {
@@ -271,6 +338,25 @@ async fn handle_list_all_notes(
     }
 }
 
+async fn handle_show_note(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+) -> ResponseResult<()> {
+    match target {
+        Some(_) => {
+            commands::show_note_command(bot, msg.unwrap(), payload).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
 async fn handle_go_home_page(
     bot: &Bot,
     msg: Option<teloxide::types::Message>,
@@ -453,7 +539,7 @@ async fn handle_set_language(
             match payload {
                 Some(data) => {
                     info!("button data is: {:#?}", data);
-                    let selected_locale = Locale::from_str(data.to_string().as_str());
+                    let selected_locale = data.to_string().parse::<Locale>().unwrap_or_default();
                     info!("selected locale is: {:?}", selected_locale);
                     commands::set_language_command(bot, msg.unwrap(), selected_locale).await?;
                     Ok(())
@@ -472,3 +558,98 @@ async fn handle_set_language(
         }
     }
 }
+
+async fn handle_react(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+) -> ResponseResult<()> {
+    match target {
+        Some(_) => {
+            commands::react_command(bot, msg.unwrap(), payload).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+async fn handle_save_favorite(
+    bot: &Bot,
+    msg: Option<teloxide::types::Message>,
+    target: Option<Chat>,
+    payload: Option<Payload>,
+) -> ResponseResult<()> {
+    match target {
+        Some(_) => {
+            commands::save_favorite_command(bot, msg.unwrap(), payload).await?;
+            Ok(())
+        }
+        // No target Chat available
+        None => {
+            warn!("target Chat is None");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bincode::Options;
+
+    use super::*;
+
+    // INFO: first byte of the codec encoding, the variant index below 251
+    fn variant_index<T: Serialize>(value: &T) -> u8 {
+        bincode::DefaultOptions::new()
+            .with_varint_encoding()
+            .serialize(value)
+            .unwrap()[0]
+    }
+
+    #[test]
+    fn topics_keep_their_index() {
+        let topics = [
+            Topic::GetRandomNote,
+            Topic::GetPersonalNote,
+            Topic::ListAllNotes,
+            Topic::DeleteNote,
+            Topic::ConfirmDelete,
+            Topic::GoHomePage,
+            Topic::GoExtraPage,
+            Topic::GoUploadPage,
+            Topic::GoCreditsPage,
+            Topic::GoLanguagePage,
+            Topic::GoHelpPage,
+            Topic::SetLanguage,
+            Topic::React,
+            Topic::SaveFavorite,
+            Topic::ShowNote,
+        ];
+
+        for (index, topic) in topics.iter().enumerate() {
+            assert_eq!(variant_index(topic) as usize, index, "{:?}", topic);
+        }
+    }
+
+    #[test]
+    fn payloads_keep_their_index() {
+        let payloads = [
+            Payload::Text("text".to_string()),
+            Payload::Username("username".to_string()),
+            Payload::UserId(1),
+            Payload::NoteId(1),
+            Payload::NoteReaction(1, Reaction::Heart),
+            Payload::PageAfter(1),
+            Payload::PageBefore(1),
+        ];
+
+        for (index, payload) in payloads.iter().enumerate() {
+            assert_eq!(variant_index(payload) as usize, index, "{:?}", payload);
+        }
+    }
+}
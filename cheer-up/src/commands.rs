@@ -1,29 +1,34 @@
 use std::io::Error;
 
-use log::{debug, info};
+use chrono::Utc;
+
+use cheer_up_core::error::ErrorCode;
+use log::{debug, info, warn};
 use rust_i18n::set_locale;
 use serde_json::json;
 use teloxide::{
-    payloads::SendMessageSetters,
+    payloads::{AnswerCallbackQuerySetters, SendMessageSetters},
     prelude::*,
-    types::{
-        InlineKeyboardButton, InlineKeyboardMarkup, InputFile, KeyboardButton, KeyboardMarkup,
-        ParseMode,
-    },
+    types::{ChatAction, InlineKeyboardMarkup, ParseMode},
     utils::command::BotCommands,
 };
 use tokio::fs;
 
 use crate::{
     callbacks::{Payload, QueryData, Topic},
+    groups::join_group,
     keyboards::{self, upload_page},
     locale::{get_user_locale_by_user_id, set_user_locale_by_user_id, Locale},
+    nav,
+    schedules::{get_schedule, remove_schedule, set_schedule, Schedule},
     stats::get_stats,
     templates::Templates,
     user::{get_user, get_user_by_id, get_user_by_telegram_id, save_user_to_db, UserId},
-    utils::{get_user_folder_path, get_user_folder_path_by_user},
     videonotes::{
-        delete_all_user_vnotes, delete_vnote_from_db, get_random_vnote, get_vnote_list_from_db,
+        count_personal_vnotes, delete_all_user_vnotes, delete_vnote_from_db, find_vnote,
+        get_favorite_vnotes, get_random_vnote, get_vnote, get_vnote_list_from_db,
+        get_vnote_page_from_db, react_to_vnote, record_vnote_view, save_favorite_vnote, send_vnote,
+        LIST_PAGE_SIZE,
     },
 };
 
@@ -46,10 +51,24 @@ pub enum Command {
     Help,
     #[command(description = "Show credits and code repo links")]
     Credits,
+    #[command(description = "Join a group of friends with an invite code")]
+    Join(String),
+    #[command(description = "Watch again the video notes you saved")]
+    Favorites,
+    #[command(
+        description = "Get a random note every day or week, i.e. /schedule 09:00 Europe/Kyiv"
+    )]
+    Schedule(String),
 }
 
 impl Command {
     pub fn parse_str(cmd: &str) -> Option<Command> {
+        // INFO: commands taking an argument, i.e. `/join <invite code>`
+        let (cmd, arg) = match cmd.trim().split_once(char::is_whitespace) {
+            Some((cmd, arg)) => (cmd, arg.trim()),
+            None => (cmd.trim(), ""),
+        };
+
         match cmd {
             "/start" => Some(Command::Start),
             "/ask_friend" => Some(Command::RandomNote),
@@ -59,6 +78,9 @@ impl Command {
             "/language" => Some(Command::Language),
             "/help" => Some(Command::Help),
             "/credits" => Some(Command::Credits),
+            "/join" => Some(Command::Join(arg.to_string())),
+            "/favorites" => Some(Command::Favorites),
+            "/schedule" => Some(Command::Schedule(arg.to_string())),
             _ => None,
         }
     }
@@ -67,13 +89,16 @@ impl Command {
 pub async fn handle_commands(bot: Bot, cmd: Command, msg: Message) -> ResponseResult<()> {
     match cmd {
         Command::Start => start_command(&bot, msg).await?,
-        Command::RandomNote => random_note_command(&bot, msg).await?,
+        Command::RandomNote => random_note_command(&bot, msg, false).await?,
         Command::Extra => extra_command(&bot, msg).await?,
         Command::Upload => upload_command(&bot, msg).await?,
         Command::List => list_command(&bot, msg).await?,
         Command::Language => language_command(&bot, msg).await?,
         Command::Help => help_command(&bot, msg).await?,
         Command::Credits => credits_command(&bot, msg).await?,
+        Command::Join(invite_code) => join_command(&bot, msg, invite_code).await?,
+        Command::Favorites => favorites_command(&bot, msg).await?,
+        Command::Schedule(args) => schedule_command(&bot, msg, args).await?,
     }
 
     Ok(())
@@ -90,18 +115,18 @@ pub async fn start_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let username = msg.chat.username().unwrap_or("Unknown User");
 
-    let template = Templates::StartPage(username.to_string());
+    let personal_notes = count_personal_vnotes(&user.id).await?;
 
-    let keyboard = keyboards::start_page(None, None, &remote_locale);
+    let template = Templates::StartPage(username.to_string(), personal_notes);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    let keyboard = keyboards::start_page(None, None, personal_notes, &remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
-pub async fn random_note_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+/// Sends a random note, with `personal` only among the ones addressed to the user.
+pub async fn random_note_command(bot: &Bot, msg: Message, personal: bool) -> ResponseResult<()> {
     let user = get_user(&msg.chat).await?;
     info!("[RANDOM_NOTE_COMMAND] user is: {:?}", user);
 
@@ -113,41 +138,36 @@ pub async fn random_note_command(bot: &Bot, msg: Message) -> ResponseResult<()>
 
     let locale_str = remote_locale.to_string();
 
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
+    bot.send_chat_action(msg.chat.id, ChatAction::UploadVideoNote)
         .await?;
 
-    let random_note = get_random_vnote(bot, &msg.chat).await?;
-    let user = get_user_by_id(&random_note.user_id).await?;
+    let Some(random_note) = get_random_vnote(&user.id, personal).await? else {
+        info!("[RANDOM_NOTE_COMMAND] no notes shared with user groups");
+        let keyboard = keyboards::group_page(&remote_locale);
+        nav::show_page(
+            bot,
+            &msg,
+            Templates::NoNotesPage.render(&locale_str),
+            keyboard,
+        )
+        .await?;
+        return Ok(());
+    };
+    let author = get_user_by_id(&random_note.user_id).await?;
 
-    let mut user_folder = get_user_folder_path_by_user(&user);
+    send_vnote(bot, msg.chat.id, &random_note, None).await?;
 
-    // this is needed because first 2 users in database
-    // are fake for boilerplate on start up
-    if random_note.user_id == 1 || random_note.user_id == 2 {
-        user_folder = format!(
-            "../_common_data/videonotes/{}_{}",
-            user.telegram_id, user.username
-        );
+    // INFO: a lost view only means the note may come back sooner, don't fail the command
+    if let Err(e) = record_vnote_view(&random_note.id, &user.id).await {
+        warn!("[RANDOM_NOTE_COMMAND] could not record note view: {}", e);
     }
-    debug!("user_folder after user check is: {}", user_folder);
 
-    let file_path = format!("{}/{}", user_folder, random_note.file_name);
-    debug!("file_path is: {}", file_path);
-    bot.send_video_note(msg.chat.id, InputFile::file(file_path))
-        .await?;
-
-    let template = Templates::RandomNotePage(user.username);
+    let template = Templates::RandomNotePage(author.username, random_note.caption.clone());
 
-    let keyboard = keyboards::random_note_page(None, &remote_locale);
+    let keyboard = keyboards::random_note_page(None, Some(random_note.id), &remote_locale);
 
-    // bot.send_message(msg.chat.id, template.render())
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    // INFO: a new page, below the note just sent
+    nav::send_page(bot, msg.chat.id, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -170,23 +190,13 @@ pub async fn delete_note_command(
 
     let locale_str = remote_locale.to_string();
 
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .await?;
-
     info!("[DELETE_NOTE_COMMAND] deleting vnote with id: {:?}", &data);
 
     let template = Templates::DeleteNotePage(data.to_string());
 
     let keyboard = keyboards::delete_note_page(Some(data), &remote_locale);
 
-    // bot.send_message(msg.chat.id, template.render())
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -208,22 +218,19 @@ pub async fn confirm_delete_command(
 
     if query_data.is_none() {
         let keyboard = keyboards::delete_note_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
+        nav::show_page(
+            bot,
+            &msg,
             Templates::ErrorDeleteNotePage.render(&locale_str),
+            keyboard,
         )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
         .await?;
         return Ok(());
     }
 
     let data = query_data.unwrap();
 
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
+    bot.send_chat_action(msg.chat.id, ChatAction::Typing)
         .await?;
 
     info!("[CONFIRM_DELETE_COMMAND] deleting vnote with id: {}", &data);
@@ -231,47 +238,42 @@ pub async fn confirm_delete_command(
     let parsed_data = data.to_string().parse::<i64>();
     if parsed_data.is_err() {
         let keyboard = keyboards::delete_note_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
+        nav::show_page(
+            bot,
+            &msg,
             Templates::ErrorDeleteNotePage.render(&locale_str),
+            keyboard,
         )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
         .await?;
         return Ok(());
     }
     let vnote_id = parsed_data.unwrap();
     // let deleted_note = delete_vnote_from_db(&vnote_id).await?;
     debug!("vnote_id is : {}", vnote_id);
-    let deleted_note = delete_vnote_from_db(&vnote_id).await;
-
-    if deleted_note.is_err() {
-        info!(
-            "[CONFIRM_DELETE_COMMAND] error deleting vnote with id: {}",
-            &vnote_id
-        );
-        let keyboard = keyboards::delete_note_result_page(&remote_locale);
-        bot.send_message(
-            msg.chat.id,
-            Templates::ErrorDeleteNotePage.render(&locale_str),
-        )
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
-        return Ok(());
-    }
-
-    let unwrapped_deleted_note = deleted_note.unwrap();
-
-    let template = Templates::SuccessDeleteNotePage(unwrapped_deleted_note.note);
+    let deleted_note = delete_vnote_from_db(&vnote_id, &user.id).await;
+
+    let template = match deleted_note {
+        Ok(Ok(deleted_note)) => Templates::SuccessDeleteNotePage(deleted_note.note),
+        Ok(Err(ErrorCode::NotNoteOwner)) => Templates::NotNoteOwnerPage,
+        Ok(Err(code)) => {
+            info!(
+                "[CONFIRM_DELETE_COMMAND] vnote {} not deleted: {:?}",
+                &vnote_id, code
+            );
+            Templates::ErrorDeleteNotePage
+        }
+        Err(e) => {
+            info!(
+                "[CONFIRM_DELETE_COMMAND] error deleting vnote with id {}: {}",
+                &vnote_id, e
+            );
+            Templates::ErrorDeleteNotePage
+        }
+    };
 
     let keyboard = keyboards::delete_note_result_page(&remote_locale);
 
-    // bot.send_message(msg.chat.id, template.render())
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -287,7 +289,7 @@ pub async fn extra_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
     let vnote_list = get_vnote_list_from_db(&msg.chat).await?;
     println!("vnote_list is: {:?}", vnote_list);
 
-    let stats = get_stats().await?;
+    let stats = get_stats(&user.id).await?;
 
     let template = Templates::ExtraPage(
         msg.chat.username().unwrap_or("Unknown user").to_string(),
@@ -299,10 +301,7 @@ pub async fn extra_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::extra_page(None, None, None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
@@ -319,7 +318,7 @@ pub async fn upload_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
     let vnote_list = get_vnote_list_from_db(&msg.chat).await?;
     println!("vnote_list is: {:?}", vnote_list);
 
-    let stats = get_stats().await?;
+    let stats = get_stats(&user.id).await?;
 
     let template = Templates::UploadPage(
         msg.chat.username().unwrap_or("Unknown user").to_string(),
@@ -331,10 +330,7 @@ pub async fn upload_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = upload_page(None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
@@ -354,7 +350,7 @@ pub async fn confirm_upload_command(bot: &Bot, msg: Message) -> ResponseResult<(
     let vnote_list = get_vnote_list_from_db(&msg.chat).await?;
     debug!("[CONFIRM_UPLOAD_COMMAND] vnote_list is: {:?}", vnote_list);
 
-    let stats = get_stats().await?;
+    let stats = get_stats(&user.id).await?;
 
     let template = Templates::UploadPage(
         msg.chat.username().unwrap_or("Unknown user").to_string(),
@@ -366,10 +362,7 @@ pub async fn confirm_upload_command(bot: &Bot, msg: Message) -> ResponseResult<(
 
     let keyboard = upload_page(None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
@@ -383,48 +376,87 @@ pub async fn list_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let locale_str = remote_locale.to_string();
 
-    // INFO: show loading indicator
-    let template = Templates::LoadingPage;
+    let (template, keyboard) = list_page(&user.id, None, &remote_locale).await?;
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
-    let vnote_list = get_vnote_list_from_db(&msg.chat).await?;
-    debug!("[LIST_COMMAND] vnote_list is: {:?}", vnote_list);
+    Ok(())
+}
 
-    for vnote in &vnote_list {
-        let user = get_user_by_id(&vnote.user_id).await?;
+/// Turns the page of the `/list` message `msg`, editing it in place.
+pub async fn list_page_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[LIST_PAGE_COMMAND] user is: {:?}", user);
 
-        let mut user_folder = get_user_folder_path_by_user(&user);
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[LIST_PAGE_COMMAND] remote_locale is: {:?}", remote_locale);
 
-        // this is needed because first 2 users in database
-        // are fake for boilerplate on start up
-        if vnote.user_id == 1 || vnote.user_id == 2 {
-            user_folder = format!(
-                "../_common_data/videonotes/{}_{}",
-                user.telegram_id, user.username
-            );
-        }
-        debug!("user_folder after user check is: {}", user_folder);
+    let locale_str = remote_locale.to_string();
 
-        let file_path = format!("{}/{}", user_folder, vnote.file_name);
-        debug!("file_path is: {}", file_path);
+    let (template, keyboard) = list_page(&user.id, query_data, &remote_locale).await?;
 
-        let keyboard = keyboards::vnote_entry(Some(Payload::NoteId(vnote.id)), &remote_locale);
-        bot.send_video_note(msg.chat.id, InputFile::file(file_path))
-            .reply_markup(keyboard)
-            .await?;
-    }
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await
+}
 
-    let template = Templates::ListPage(vnote_list.len().to_string());
+async fn list_page(
+    user_id: &i64,
+    cursor: Option<Payload>,
+    locale: &Locale,
+) -> ResponseResult<(Templates, InlineKeyboardMarkup)> {
+    let (after, before) = match cursor {
+        Some(Payload::PageAfter(cursor)) => (Some(cursor), None),
+        Some(Payload::PageBefore(cursor)) => (None, Some(cursor)),
+        _ => (None, None),
+    };
 
-    let keyboard = keyboards::list_notes_page(None, None, &remote_locale);
+    let vnote_page = get_vnote_page_from_db(user_id, after, before).await?;
+    debug!("[LIST_PAGE] vnote_page is: {:?}", vnote_page);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    let (page, pages) = vnote_page.position(LIST_PAGE_SIZE);
+
+    let template = Templates::ListPage(vnote_page.total.to_string(), page as usize, pages as usize);
+    let keyboard = keyboards::list_notes_page(&vnote_page, None, None, locale);
+
+    Ok((template, keyboard))
+}
+
+/// Sends a note picked from the `/list` page.
+pub async fn show_note_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[SHOW_NOTE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[SHOW_NOTE_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    let vnote = match query_data {
+        Some(Payload::NoteId(note_id)) => find_vnote(&note_id).await?,
+        _ => None,
+    };
+
+    match vnote {
+        Some(vnote) => {
+            let keyboard = keyboards::vnote_entry(Some(Payload::NoteId(vnote.id)), &remote_locale);
+            send_vnote(bot, msg.chat.id, &vnote, Some(keyboard)).await?;
+        }
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                Templates::NoteUnavailablePage.render(&locale_str),
+            )
+            .parse_mode(ParseMode::Html)
+            .await?;
+        }
+    }
 
     Ok(())
 }
@@ -442,11 +474,44 @@ pub async fn language_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::language_page(&remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .disable_web_page_preview(true)
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+    Ok(())
+}
+
+/// Answers taps on buttons whose data was forged, or kept in memory before a restart.
+pub async fn invalid_button_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[INVALID_BUTTON_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+
+    let locale_str = remote_locale.to_string();
+
+    let template = Templates::InvalidButtonPage;
+
+    let keyboard = keyboards::delete_note_result_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+    Ok(())
+}
+
+/// Answers taps on buttons of a page left behind by a newer one, and removes them.
+pub async fn stale_button_command(bot: &Bot, msg: Message, query_id: String) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[STALE_BUTTON_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+
+    let locale_str = remote_locale.to_string();
+
+    let template = Templates::StaleButtonAlert;
+
+    bot.answer_callback_query(query_id)
+        .text(template.render(&locale_str))
+        .show_alert(true)
         .await?;
+
+    nav::drop_keyboard(bot, msg.chat.id, msg.id).await;
     Ok(())
 }
 
@@ -463,10 +528,7 @@ pub async fn help_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::help_page(None, None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
     Ok(())
 }
 
@@ -483,12 +545,174 @@ pub async fn credits_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
 
     let keyboard = keyboards::credits_page(None, None, &remote_locale);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .disable_web_page_preview(true)
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn join_command(bot: &Bot, msg: Message, invite_code: String) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[JOIN_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[JOIN_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    let group = match invite_code.trim() {
+        "" => None,
+        code => join_group(code, &user.id).await?,
+    };
+    info!("[JOIN_COMMAND] joined group is: {:?}", group);
+
+    let template = match group {
+        Some(group) => Templates::JoinGroupPage(group.name),
+        None => Templates::ErrorJoinGroupPage,
+    };
+
+    let keyboard = keyboards::group_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn react_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[REACT_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[REACT_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    let template = match query_data {
+        Some(Payload::NoteReaction(note_id, reaction)) => {
+            match react_to_vnote(&note_id, &user.id, reaction).await? {
+                Some(_) => {
+                    let note = get_vnote(&note_id).await?;
+                    let author = get_user_by_id(&note.user_id).await?;
+                    Templates::ReactionSentPage(reaction.emoji().to_string(), author.username)
+                }
+                None => Templates::ErrorReactionPage,
+            }
+        }
+        _ => Templates::ErrorReactionPage,
+    };
+
+    let keyboard = keyboards::random_note_page(None, None, &remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn save_favorite_command(
+    bot: &Bot,
+    msg: Message,
+    query_data: Option<Payload>,
+) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[SAVE_FAVORITE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!(
+        "[SAVE_FAVORITE_COMMAND] remote_locale is: {:?}",
+        remote_locale
+    );
+
+    let locale_str = remote_locale.to_string();
+
+    let template = match query_data {
+        Some(Payload::NoteId(note_id)) => match save_favorite_vnote(&note_id, &user.id).await? {
+            Some(note) => {
+                let author = get_user_by_id(&note.user_id).await?;
+                Templates::FavoriteSavedPage(author.username)
+            }
+            None => Templates::ErrorReactionPage,
+        },
+        _ => Templates::ErrorReactionPage,
+    };
+
+    let keyboard = keyboards::random_note_page(None, None, &remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
+
+pub async fn favorites_command(bot: &Bot, msg: Message) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[FAVORITES_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[FAVORITES_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    bot.send_chat_action(msg.chat.id, ChatAction::UploadVideoNote)
         .await?;
 
+    let vnote_list = get_favorite_vnotes(&user.id).await?;
+    debug!("vnote_list is: {:?}", vnote_list);
+
+    for vnote in &vnote_list {
+        send_vnote(bot, msg.chat.id, vnote, None).await?;
+    }
+
+    let template = match vnote_list.len() {
+        0 => Templates::NoFavoritesPage,
+        total_notes => Templates::FavoritesPage(total_notes),
+    };
+
+    let keyboard = keyboards::random_note_page(None, None, &remote_locale);
+
+    if vnote_list.is_empty() {
+        nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+    } else {
+        // INFO: a new page, below the notes just sent
+        nav::send_page(bot, msg.chat.id, template.render(&locale_str), keyboard).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn schedule_command(bot: &Bot, msg: Message, args: String) -> ResponseResult<()> {
+    let user = get_user(&msg.chat).await?;
+    info!("[SCHEDULE_COMMAND] user is: {:?}", user);
+
+    let remote_locale = get_user_locale_by_user_id(&user.id).await?;
+    info!("[SCHEDULE_COMMAND] remote_locale is: {:?}", remote_locale);
+
+    let locale_str = remote_locale.to_string();
+
+    // INFO: `/schedule` shows the current schedule, `/schedule off` removes it
+    let template = match args.trim() {
+        "" => match get_schedule(&user.id).await? {
+            Some(subscription) => Templates::SchedulePage(subscription),
+            None => Templates::NoSchedulePage,
+        },
+        input if input.eq_ignore_ascii_case("off") => {
+            remove_schedule(&user.id).await?;
+            Templates::ScheduleRemovedPage
+        }
+        input => match Schedule::parse(input, Utc::now()) {
+            Ok(schedule) => Templates::SchedulePage(set_schedule(&user.id, &schedule).await?),
+            Err(e) => {
+                info!("[SCHEDULE_COMMAND] invalid schedule {:?}: {}", input, e);
+                Templates::ErrorSchedulePage
+            }
+        },
+    };
+
+    let keyboard = keyboards::group_page(&remote_locale);
+
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
+
     Ok(())
 }
 
@@ -504,18 +728,16 @@ pub async fn set_language_command(bot: &Bot, msg: Message, locale: Locale) -> Re
 
     let username = msg.chat.username().unwrap_or("Unknown User");
 
-    let template = Templates::StartPage(username.to_string());
+    let personal_notes = count_personal_vnotes(&user.id).await?;
 
-    let keyboard = keyboards::start_page(None, None, &remote_locale);
+    let template = Templates::StartPage(username.to_string(), personal_notes);
+
+    let keyboard = keyboards::start_page(None, None, personal_notes, &remote_locale);
 
     let rendered_template = template.render(&locale_str);
     debug!("rendered_template: {:?}", rendered_template);
 
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
-        .reply_markup(keyboard)
-        .disable_web_page_preview(true)
-        .await?;
+    nav::show_page(bot, &msg, template.render(&locale_str), keyboard).await?;
 
     Ok(())
 }
@@ -0,0 +1,19 @@
+use teloxide::requests::ResponseResult;
+
+use cheer_up_core::client;
+
+pub use cheer_up_core::group::{Group, JoinGroup};
+
+/// Adds the user to the group with `invite_code`, `None` if the code is unknown.
+pub async fn join_group(invite_code: &str, user_id: &i64) -> ResponseResult<Option<Group>> {
+    let join = JoinGroup {
+        user_id: *user_id,
+        invite_code: invite_code.to_string(),
+    };
+
+    match client::global().join_group(&join).await {
+        Ok(group) => Ok(Some(group)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -3,52 +3,91 @@ use teloxide::types::{InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyb
 use crate::buttons::{
     ask_friend_button, confirm_delete_button, delete_note_button, go_to_credits_button,
     go_to_extra_button, go_to_help_button, go_to_home_button, go_to_language_button,
-    go_to_upload_button, list_all_notes_button, set_language_EN_button, set_language_ES_button,
-    set_language_IT_button, set_language_UA_button,
+    go_to_upload_button, list_all_notes_button, next_page_button, personal_note_button,
+    prev_page_button, react_button, save_favorite_button, set_language_EN_button,
+    set_language_ES_button, set_language_IT_button, set_language_UA_button, show_note_button,
 };
 use crate::callbacks::{Payload, QueryData};
 use crate::locale::Locale;
+use crate::videonotes::{NotePage, Reaction};
 
 pub fn start_page(
     ask_friend_query: Option<Payload>,
     go_extra_query: Option<Payload>,
+    personal_notes: usize,
     locale: &Locale,
 ) -> InlineKeyboardMarkup {
     let ask_friend_button = ask_friend_button(ask_friend_query.clone(), locale);
     // let go_to_extra_button = make_button(&t!("start_page.buttons.go_extra"), go_extra_query);
     let go_to_extra_button = go_to_extra_button(go_extra_query.clone(), locale);
 
-    let keyboard_buttons = vec![vec![ask_friend_button, go_to_extra_button]];
+    let mut keyboard_buttons = vec![vec![ask_friend_button, go_to_extra_button]];
+
+    if personal_notes > 0 {
+        keyboard_buttons.push(vec![personal_note_button(None, locale)]);
+    }
 
     InlineKeyboardMarkup::new(keyboard_buttons)
 }
 
 pub fn random_note_page(
     ask_friend_payload: Option<Payload>,
+    note_id: Option<i64>,
     locale: &Locale,
 ) -> InlineKeyboardMarkup {
-    let row_1 = vec![ask_friend_button(ask_friend_payload.clone(), locale)];
-
-    let row_2 = vec![go_to_home_button(None, locale)];
-
-    let keyboard_buttons = vec![row_1, row_2];
+    let mut keyboard_buttons = vec![];
+
+    // INFO: reactions only make sense right below the note they are about
+    if let Some(note_id) = note_id {
+        keyboard_buttons.push(
+            Reaction::ALL
+                .iter()
+                .map(|reaction| react_button(note_id, *reaction))
+                .collect(),
+        );
+        keyboard_buttons.push(vec![save_favorite_button(
+            Some(Payload::NoteId(note_id)),
+            locale,
+        )]);
+    }
+
+    keyboard_buttons.push(vec![ask_friend_button(ask_friend_payload.clone(), locale)]);
+
+    keyboard_buttons.push(vec![go_to_home_button(None, locale)]);
 
     InlineKeyboardMarkup::new(keyboard_buttons)
 }
 
 pub fn list_notes_page(
+    vnote_page: &NotePage,
     go_extra_payload: Option<Payload>,
     go_help_payload: Option<Payload>,
     locale: &Locale,
 ) -> InlineKeyboardMarkup {
-    let row_1 = vec![
+    // INFO: one button per note, tapping it sends the note below the list
+    let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = vnote_page
+        .notes
+        .iter()
+        .map(|vnote| vec![show_note_button(vnote, locale)])
+        .collect();
+
+    let mut page_row = vec![];
+    if let Some(cursor) = vnote_page.prev_cursor {
+        page_row.push(prev_page_button(Some(Payload::PageBefore(cursor)), locale));
+    }
+    if let Some(cursor) = vnote_page.next_cursor {
+        page_row.push(next_page_button(Some(Payload::PageAfter(cursor)), locale));
+    }
+    if !page_row.is_empty() {
+        keyboard_buttons.push(page_row);
+    }
+
+    keyboard_buttons.push(vec![
         go_to_extra_button(go_extra_payload.clone(), locale),
         go_to_help_button(go_help_payload.clone(), locale),
-    ];
+    ]);
 
-    let row_2 = vec![go_to_home_button(None, locale)];
-
-    let keyboard_buttons = vec![row_1, row_2];
+    keyboard_buttons.push(vec![go_to_home_button(None, locale)]);
 
     InlineKeyboardMarkup::new(keyboard_buttons)
 }
@@ -130,6 +169,14 @@ pub fn upload_result_page(locale: &Locale) -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(keyboard_buttons)
 }
 
+pub fn group_page(locale: &Locale) -> InlineKeyboardMarkup {
+    let row_1 = vec![go_to_home_button(None, locale)];
+
+    let keyboard_buttons = vec![row_1];
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
 pub fn credits_page(
     go_extra_payload: Option<Payload>,
     go_help_payload: Option<Payload>,
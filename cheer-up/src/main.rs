@@ -5,29 +5,60 @@ rust_i18n::i18n!("locales", fallback = "en");
 use rust_i18n::set_locale;
 
 // use log;
+use cheer_up_core::{
+    callback::{self, CallbackCodec},
+    client, media, CheerUpApiClient,
+};
+use clap::Parser;
 use teloxide::{prelude::*, RequestError};
 
+use crate::config::{BotArgs, BotConfig};
+
 mod buttons;
 mod callbacks;
 mod commands;
+mod config;
+mod groups;
 mod keyboards;
 mod locale;
 mod messages;
+mod nav;
+mod schedules;
 mod stats;
 mod templates;
 mod user;
 mod utils;
 mod videonotes;
 
+#[derive(Debug, Parser)]
+#[command(about = "CheerUp main bot")]
+struct Cli {
+    #[command(flatten)]
+    bot: BotArgs,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), RequestError> {
     pretty_env_logger::init();
     dotenvy::dotenv().ok();
 
-    let app_locale = dotenvy::var("LOCALE").unwrap_or("en".to_string());
-    set_locale(&app_locale);
+    // INFO: validate configuration once, before the bot starts answering users
+    let cli = Cli::parse();
+    let config = BotConfig::load(cli.bot).unwrap_or_else(|e| {
+        eprintln!("invalid configuration: {}", e);
+        std::process::exit(1);
+    });
+
+    set_locale(config.default_locale.code());
+
+    client::init(CheerUpApiClient::new(&config.api_url).with_api_key(&config.api_key));
+    callback::init(CallbackCodec::new(config.callback_secret.as_bytes()));
+    media::init(config.media.build());
+
+    let bot = Bot::new(&config.telegram_token);
+    config::init(config);
 
-    let bot = Bot::from_env();
+    tokio::spawn(schedules::run(bot.clone()));
 
     // teloxide::repl(bot, handle_input).await;
     let handler = dptree::entry()
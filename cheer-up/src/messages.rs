@@ -1,12 +1,12 @@
 use log::{debug, info};
 use teloxide::{
     prelude::*,
-    types::{Me, ParseMode},
+    types::{ChatAction, Me, ParseMode},
 };
 
 use crate::user::*;
 use crate::videonotes::*;
-use crate::{commands::*, keyboards, locale::get_user_locale_by_user_id, templates::*};
+use crate::{commands::*, keyboards, locale::get_user_locale_by_user_id, nav, templates::*};
 
 #[derive(Debug, Clone, Copy)]
 pub enum MessageType {
@@ -68,7 +68,11 @@ impl MessageType {
 }
 
 pub async fn handle_message(bot: Bot, msg: Message, me: Me) -> ResponseResult<()> {
-    let user = get_user(&msg.chat).await?;
+    // INFO: every conversation starts with a message, this is where users get registered
+    let user = match msg.from() {
+        Some(from) => register_user(from).await?,
+        None => get_user(&msg.chat).await?,
+    };
     info!("[HANDLE_MESSAGE] user is: {:?}", user);
 
     let remote_locale = get_user_locale_by_user_id(&user.id).await?;
@@ -77,10 +81,7 @@ pub async fn handle_message(bot: Bot, msg: Message, me: Me) -> ResponseResult<()
     let locale_str = remote_locale.to_string();
 
     // INFO: show loading indicator
-    let template = Templates::LoadingPage;
-
-    bot.send_message(msg.chat.id, template.render(&locale_str))
-        .parse_mode(ParseMode::Html)
+    bot.send_chat_action(msg.chat.id, ChatAction::Typing)
         .await?;
 
     // INFO: parse message to intercept videonotes uploadings
@@ -99,38 +100,45 @@ pub async fn handle_message(bot: Bot, msg: Message, me: Me) -> ResponseResult<()
             if save_user.is_err() {
                 info!("[HANDLE_MESSAGE] an error occurred while saving user to db");
             }
-            // INFO: upload vnote to server disk
-            let upload_result = upload_vnote(&bot, vnote, &msg.chat).await;
-            if upload_result.is_err() {
-                info!("[HANDLE_MESSAGE] an error occurred while saving note");
-                let keyboard = keyboards::upload_result_page(&remote_locale);
-                bot.send_message(msg.chat.id, Templates::ErrorUploadPage.render(&locale_str))
-                    .parse_mode(ParseMode::Html)
-                    .reply_markup(keyboard)
+            // INFO: copy vnote to the media store, its file_id is useless to the manager bot
+            let media_key = match upload_vnote(&bot, vnote, &msg.chat).await {
+                Ok(key) => key,
+                Err(e) => {
+                    info!(
+                        "[HANDLE_MESSAGE] an error occurred while saving note: {}",
+                        e
+                    );
+                    let keyboard = keyboards::upload_result_page(&remote_locale);
+                    nav::show_page(
+                        &bot,
+                        &msg,
+                        Templates::ErrorUploadPage.render(&locale_str),
+                        keyboard,
+                    )
                     .await?;
-                return Ok(());
-            }
+                    return Ok(());
+                }
+            };
 
             // INFO: save vnote to db
-            let save_vnote = save_vnote_to_db(&vnote.file.id, &msg.chat).await;
+            let save_vnote = save_vnote_to_db(vnote, &msg.chat, media_key).await;
             if save_vnote.is_err() {
                 info!("[HANDLE_MESSAGE] an error occurred while saving note");
                 let keyboard = keyboards::upload_result_page(&remote_locale);
-                bot.send_message(msg.chat.id, Templates::ErrorUploadPage.render(&locale_str))
-                    .parse_mode(ParseMode::Html)
-                    .reply_markup(keyboard)
-                    .await?;
+                nav::show_page(
+                    &bot,
+                    &msg,
+                    Templates::ErrorUploadPage.render(&locale_str),
+                    keyboard,
+                )
+                .await?;
                 return Ok(());
             }
 
             let template = Templates::SuccessUploadPage;
             let keyboard = keyboards::upload_result_page(&remote_locale);
 
-            // bot.send_message(msg.chat.id, template.render())
-            bot.send_message(msg.chat.id, template.render(&locale_str))
-                .parse_mode(ParseMode::Html)
-                .reply_markup(keyboard)
-                .await?;
+            nav::show_page(&bot, &msg, template.render(&locale_str), keyboard).await?;
             Ok(())
         }
         MessageType::Text => {
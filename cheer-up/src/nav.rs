@@ -0,0 +1,89 @@
+use cheer_up_core::nav;
+use log::{debug, warn};
+use teloxide::{
+    payloads::{EditMessageTextSetters, SendMessageSetters},
+    prelude::*,
+    types::{ChatId, InlineKeyboardMarkup, Message, MessageId, ParseMode},
+    ApiError, RequestError,
+};
+
+/// Shows a page with its keyboard: when `msg` is the page whose button was tapped
+/// it is edited in place, otherwise a new page is sent below `msg`.
+pub async fn show_page(
+    bot: &Bot,
+    msg: &Message,
+    text: String,
+    keyboard: InlineKeyboardMarkup,
+) -> ResponseResult<()> {
+    if !is_page(msg) {
+        return send_page(bot, msg.chat.id, text, keyboard).await;
+    }
+
+    let edited = bot
+        .edit_message_text(msg.chat.id, msg.id, &text)
+        .parse_mode(ParseMode::Html)
+        .disable_web_page_preview(true)
+        .reply_markup(keyboard.clone())
+        .await;
+
+    match edited {
+        Ok(_) => {
+            nav::global().set_page(msg.chat.id.0, msg.id.0);
+            Ok(())
+        }
+        // INFO: double taps ask for the page already shown
+        Err(RequestError::Api(ApiError::MessageNotModified)) => Ok(()),
+        // INFO: messages older than 48 hours cannot be edited anymore
+        Err(RequestError::Api(ApiError::MessageCantBeEdited | ApiError::MessageToEditNotFound)) => {
+            send_page(bot, msg.chat.id, text, keyboard).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Sends a new page with its keyboard, i.e. below the video notes just sent, and
+/// removes the keyboard of the page it replaces.
+pub async fn send_page(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: String,
+    keyboard: InlineKeyboardMarkup,
+) -> ResponseResult<()> {
+    let sent = bot
+        .send_message(chat_id, text)
+        .parse_mode(ParseMode::Html)
+        .disable_web_page_preview(true)
+        .reply_markup(keyboard)
+        .await?;
+
+    if let Some(previous) = nav::global().set_page(chat_id.0, sent.id.0) {
+        drop_keyboard(bot, chat_id, MessageId(previous)).await;
+    }
+
+    Ok(())
+}
+
+/// Whether `msg` is a page left behind by a newer one, its buttons are stale.
+pub fn is_stale(msg: &Message) -> bool {
+    is_page(msg) && nav::global().is_stale(msg.chat.id.0, msg.id.0)
+}
+
+/// Removes the keyboard of a page, failing only logs since the page stays readable.
+pub async fn drop_keyboard(bot: &Bot, chat_id: ChatId, message_id: MessageId) {
+    match bot.edit_message_reply_markup(chat_id, message_id).await {
+        Ok(_) => {}
+        // INFO: the page had no keyboard left
+        Err(RequestError::Api(ApiError::MessageNotModified)) => {
+            debug!("[DROP_KEYBOARD] page {} has no keyboard", message_id.0)
+        }
+        Err(e) => warn!(
+            "[DROP_KEYBOARD] cannot remove keyboard of page {}: {}",
+            message_id.0, e
+        ),
+    }
+}
+
+// INFO: pages are the text messages of the bot, video notes are never edited
+fn is_page(msg: &Message) -> bool {
+    msg.text().is_some() && msg.from().is_some_and(|from| from.is_bot)
+}
@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use log::{info, warn};
+use teloxide::prelude::*;
+
+use cheer_up_core::client;
+
+pub use cheer_up_core::subscription::{
+    format_delivery_time, Frequency, Schedule, Subscription, SubscriptionDelivered,
+};
+
+use crate::keyboards;
+use crate::locale::get_user_locale_by_user_id;
+use crate::nav;
+use crate::templates::Templates;
+use crate::user::get_user_by_id;
+use crate::videonotes::{get_random_vnote, record_vnote_view, send_vnote};
+
+/// How often due deliveries are looked up, also the worst delivery delay.
+pub const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Schedule of `user_id`, `None` if the user has no subscription.
+pub async fn get_schedule(user_id: &i64) -> ResponseResult<Option<Subscription>> {
+    match client::global().get_subscription(*user_id).await {
+        Ok(subscription) => Ok(Some(subscription)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn set_schedule(user_id: &i64, schedule: &Schedule) -> ResponseResult<Subscription> {
+    let new_subscription = schedule.to_new_subscription(Utc::now());
+    let subscription = client::global()
+        .set_subscription(*user_id, &new_subscription)
+        .await?;
+
+    Ok(subscription)
+}
+
+pub async fn remove_schedule(user_id: &i64) -> ResponseResult<()> {
+    client::global().delete_subscription(*user_id).await?;
+
+    Ok(())
+}
+
+/// Delivers scheduled notes until the bot stops, spawned next to the dispatcher.
+pub async fn run(bot: Bot) {
+    let mut interval = tokio::time::interval(SCHEDULE_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = deliver_due_notes(&bot).await {
+            warn!("[SCHEDULES] could not deliver scheduled notes: {}", e);
+        }
+    }
+}
+
+async fn deliver_due_notes(bot: &Bot) -> ResponseResult<()> {
+    let subscriptions = client::global().list_due_subscriptions().await?;
+    info!("[SCHEDULES] {} deliveries due", subscriptions.len());
+
+    for subscription in subscriptions {
+        let schedule = match Schedule::from_subscription(&subscription) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                warn!(
+                    "[SCHEDULES] dropping invalid schedule of user {}: {}",
+                    subscription.user_id, e
+                );
+                if let Err(e) = remove_schedule(&subscription.user_id).await {
+                    warn!(
+                        "[SCHEDULES] could not remove schedule of user {}: {}",
+                        subscription.user_id, e
+                    );
+                }
+                continue;
+            }
+        };
+
+        // INFO: failed deliveries (i.e. the user blocked the bot) are not retried
+        if let Err(e) = deliver_note(bot, &subscription).await {
+            warn!(
+                "[SCHEDULES] could not deliver note to user {}: {}",
+                subscription.user_id, e
+            );
+        }
+
+        // INFO: computed from now, after a downtime missed deliveries are caught
+        //       up with a single note instead of one per missed day
+        let delivered = SubscriptionDelivered {
+            next_delivery_at: format_delivery_time(schedule.next_delivery(Utc::now())),
+        };
+        // INFO: one failing user must not hold back the deliveries after it
+        if let Err(e) = client::global()
+            .mark_subscription_delivered(subscription.user_id, &delivered)
+            .await
+        {
+            warn!(
+                "[SCHEDULES] could not mark delivery of user {}: {}",
+                subscription.user_id, e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn deliver_note(bot: &Bot, subscription: &Subscription) -> ResponseResult<()> {
+    let Some(random_note) = get_random_vnote(&subscription.user_id, false).await? else {
+        info!(
+            "[SCHEDULES] no notes shared with user {}, skipping delivery",
+            subscription.user_id
+        );
+        return Ok(());
+    };
+
+    let chat_id = ChatId(subscription.telegram_id);
+
+    let remote_locale = get_user_locale_by_user_id(&subscription.user_id).await?;
+
+    let locale_str = remote_locale.to_string();
+
+    let author = get_user_by_id(&random_note.user_id).await?;
+
+    send_vnote(bot, chat_id, &random_note, None).await?;
+
+    if let Err(e) = record_vnote_view(&random_note.id, &subscription.user_id).await {
+        warn!("[SCHEDULES] could not record note view: {}", e);
+    }
+
+    let template = Templates::ScheduledNotePage(author.username, random_note.caption.clone());
+
+    let keyboard = keyboards::random_note_page(None, Some(random_note.id), &remote_locale);
+
+    nav::send_page(bot, chat_id, template.render(&locale_str), keyboard).await?;
+
+    Ok(())
+}
@@ -1,46 +1,21 @@
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use teloxide::{requests::ResponseResult, types::Chat};
+use teloxide::requests::ResponseResult;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Stats {
-    pub total_videonotes: i32,
-    pub users: Vec<UserStats>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserStats {
-    pub username: String,
-    pub videonotes: i64,
-}
+use cheer_up_core::client;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct StatsBody<T> {
-    stats: T,
-}
-
-pub async fn get_stats() -> ResponseResult<Stats> {
-    let client = Client::new();
+pub use cheer_up_core::stats::{Stats, StatsQuery, UserStats};
 
-    let notes_stats = client
-        .get(format!("http://0.0.0.0:1989/api/stats"))
-        .send()
-        .await?
-        .json::<StatsBody<Stats>>()
-        .await?;
+/// Stats of the groups `user_id` belongs to.
+pub async fn get_stats(user_id: &i64) -> ResponseResult<Stats> {
+    let query = StatsQuery {
+        for_user: Some(*user_id),
+    };
+    let notes_stats = client::global().get_stats(&query).await?;
 
-    Ok(notes_stats.stats)
+    Ok(notes_stats)
 }
 
 pub async fn get_user_stats_by_id(user_id: &i64) -> ResponseResult<UserStats> {
-    let client = Client::new();
-
-    let user_stats = client
-        .get(format!("http://0.0.0.0:1989/api/stats/user/{}", user_id))
-        .send()
-        .await?
-        .json::<StatsBody<UserStats>>()
-        .await?;
+    let user_stats = client::global().get_user_stats(*user_id).await?;
 
-    Ok(user_stats.stats)
+    Ok(user_stats)
 }
@@ -1,49 +1,61 @@
-use dotenvy as dotenv;
 use log::debug;
+use teloxide::utils::html;
 
+use crate::config;
+use crate::schedules::{Frequency, Subscription};
 use crate::stats::UserStats;
 
 #[derive(Debug, Clone)]
 pub enum Templates {
-    LoadingPage,
-    StartPage(String),
-    RandomNotePage(String),
+    StartPage(String, usize),
+    // INFO: (author username, note caption)
+    RandomNotePage(String, Option<String>),
     DeleteNotePage(String),
     SuccessDeleteNotePage(String),
     ErrorDeleteNotePage,
+    NotNoteOwnerPage,
     ExtraPage(String, String, String, Vec<UserStats>),
     UploadPage(String, String, String, Vec<UserStats>),
     SuccessUploadPage,
     ErrorUploadPage,
-    ListPage(String),
+    // INFO: (total notes, page number, page count)
+    ListPage(String, usize, usize),
     LanguagePage,
     HelpPage,
     CreditsPage,
     UnsupportedInputPage(String),
+    NoNotesPage,
+    ReactionSentPage(String, String),
+    FavoriteSavedPage(String),
+    ErrorReactionPage,
+    NoteUnavailablePage,
+    FavoritesPage(usize),
+    NoFavoritesPage,
+    SchedulePage(Subscription),
+    NoSchedulePage,
+    ScheduleRemovedPage,
+    ErrorSchedulePage,
+    ScheduledNotePage(String, Option<String>),
+    JoinGroupPage(String),
+    ErrorJoinGroupPage,
+    InvalidButtonPage,
+    StaleButtonAlert,
 }
 
 impl Templates {
     pub fn render(&self, locale: &str) -> String {
         debug!("rendering with locale: {:?}", locale);
-        let author =
-            dotenv::var("AUTHOR").expect("error loading author from envirenment variables");
-
-        let profile_name = dotenv::var("AUTHOR_PROFILE_NAME")
-            .expect("error loading author from envirenment variables");
-
-        let profile_url = dotenv::var("AUTHOR_PROFILE_URL")
-            .expect("error loading author profile url from envirenment variables");
-
-        let repo_url = dotenv::var("CODE_REPO_URL")
-            .expect("error loading code repo url from envirenment variables");
+        let credits = &config::get().credits;
 
         match self {
-            Templates::LoadingPage => loading_page(locale),
-            Templates::StartPage(user) => start_page(user, locale),
-            Templates::RandomNotePage(user) => random_note_page(user, locale),
+            Templates::StartPage(user, personal_notes) => start_page(user, *personal_notes, locale),
+            Templates::RandomNotePage(user, caption) => {
+                random_note_page(user, caption.as_deref(), locale)
+            }
             Templates::DeleteNotePage(note_id) => delete_note_page(note_id, locale),
             Templates::SuccessDeleteNotePage(note_id) => success_delete_note_page(note_id, locale),
             Templates::ErrorDeleteNotePage => error_delete_note_page(locale),
+            Templates::NotNoteOwnerPage => not_note_owner_page(locale),
             Templates::ExtraPage(user, total_notes, total_users, user_videonotes_list) => {
                 extra_page(user, total_notes, total_users, user_videonotes_list, locale)
             }
@@ -52,24 +64,71 @@ impl Templates {
             }
             Templates::SuccessUploadPage => success_upload_page(locale),
             Templates::ErrorUploadPage => error_upload_page(locale),
-            Templates::ListPage(total_notes) => list_page(total_notes, locale),
-            Templates::LanguagePage => language_page(&repo_url, locale),
-            Templates::HelpPage => help_page(locale),
-            Templates::CreditsPage => {
-                credits_page(&author, &profile_name, &profile_url, &repo_url, locale)
+            Templates::ListPage(total_notes, page, pages) => {
+                list_page(total_notes, *page, *pages, locale)
             }
+            Templates::LanguagePage => language_page(&credits.repo_url, locale),
+            Templates::HelpPage => help_page(locale),
+            Templates::CreditsPage => credits_page(
+                &credits.author,
+                &credits.profile_name,
+                &credits.profile_url,
+                &credits.repo_url,
+                locale,
+            ),
             Templates::UnsupportedInputPage(input) => unsupported_input_page(input, locale),
+            Templates::NoNotesPage => no_notes_page(locale),
+            Templates::ReactionSentPage(reaction, author) => {
+                reaction_sent_page(reaction, author, locale)
+            }
+            Templates::FavoriteSavedPage(author) => favorite_saved_page(author, locale),
+            Templates::ErrorReactionPage => error_reaction_page(locale),
+            Templates::NoteUnavailablePage => note_unavailable_page(locale),
+            Templates::FavoritesPage(total_notes) => favorites_page(total_notes, locale),
+            Templates::NoFavoritesPage => no_favorites_page(locale),
+            Templates::SchedulePage(subscription) => schedule_page(subscription, locale),
+            Templates::NoSchedulePage => no_schedule_page(locale),
+            Templates::ScheduleRemovedPage => schedule_removed_page(locale),
+            Templates::ErrorSchedulePage => error_schedule_page(locale),
+            Templates::ScheduledNotePage(user, caption) => {
+                scheduled_note_page(user, caption.as_deref(), locale)
+            }
+            Templates::JoinGroupPage(group) => join_group_page(group, locale),
+            Templates::ErrorJoinGroupPage => error_join_group_page(locale),
+            Templates::InvalidButtonPage => invalid_button_page(locale),
+            Templates::StaleButtonAlert => stale_button_alert(locale),
         }
     }
 }
 
-fn start_page(user: &str, locale: &str) -> String {
+fn start_page(user: &str, personal_notes: usize, locale: &str) -> String {
     debug!("rendering with locale: {:?}", locale);
-    format!("{}", t!("start_page", locale = locale, user = user))
+
+    let personal_notes = match personal_notes {
+        0 => String::new(),
+        count => format!(
+            "\n\n{}",
+            t!("start_page_personal_notes", locale = locale, count = count)
+        ),
+    };
+
+    format!(
+        "{}",
+        t!(
+            "start_page",
+            locale = locale,
+            user = user,
+            personal_notes = personal_notes
+        )
+    )
 }
 
-fn random_note_page(user: &str, locale: &str) -> String {
-    format!("{}", t!("random_note_page", locale = locale, user = user))
+fn random_note_page(user: &str, caption: Option<&str>, locale: &str) -> String {
+    format!(
+        "{}{}",
+        t!("random_note_page", locale = locale, user = user),
+        note_caption(caption, locale)
+    )
 }
 
 fn delete_note_page(note_id: &str, locale: &str) -> String {
@@ -94,8 +153,8 @@ fn error_delete_note_page(locale: &str) -> String {
     format!("{}", t!("error_delete_note_page", locale = locale))
 }
 
-fn loading_page(locale: &str) -> String {
-    format!("{}", t!("loading_page", locale = locale))
+fn not_note_owner_page(locale: &str) -> String {
+    format!("{}", t!("not_note_owner_page", locale = locale))
 }
 
 fn extra_page(
@@ -180,10 +239,24 @@ fn error_upload_page(locale: &str) -> String {
     format!("{}", t!("error_upload_page", locale = locale))
 }
 
-fn list_page(total_notes: &str, locale: &str) -> String {
+fn list_page(total_notes: &str, page: usize, pages: usize, locale: &str) -> String {
+    let position = match pages {
+        0 | 1 => "".to_string(),
+        pages => format!(
+            "\n{}",
+            t!(
+                "list_page_position",
+                locale = locale,
+                page = page,
+                pages = pages
+            )
+        ),
+    };
+
     format!(
-        "{}",
-        t!("list_page", locale = locale, total_notes = total_notes)
+        "{}{}",
+        t!("list_page", locale = locale, total_notes = total_notes),
+        position
     )
 }
 
@@ -252,3 +325,136 @@ fn unsupported_input_page(input_type: &str, locale: &str) -> String {
 
     format!("{}", t!("unsupported_page.other_media", locale = locale))
 }
+
+fn no_notes_page(locale: &str) -> String {
+    format!("{}", t!("no_notes_page", locale = locale))
+}
+
+fn join_group_page(group: &str, locale: &str) -> String {
+    format!("{}", t!("join_group_page", locale = locale, group = group))
+}
+
+fn error_join_group_page(locale: &str) -> String {
+    format!("{}", t!("error_join_group_page", locale = locale))
+}
+
+fn reaction_sent_page(reaction: &str, author: &str, locale: &str) -> String {
+    format!(
+        "{}",
+        t!(
+            "reaction_sent_page",
+            locale = locale,
+            reaction = reaction,
+            author = author
+        )
+    )
+}
+
+fn favorite_saved_page(author: &str, locale: &str) -> String {
+    format!(
+        "{}",
+        t!("favorite_saved_page", locale = locale, author = author)
+    )
+}
+
+fn error_reaction_page(locale: &str) -> String {
+    format!("{}", t!("error_reaction_page", locale = locale))
+}
+
+fn note_unavailable_page(locale: &str) -> String {
+    format!("{}", t!("note_unavailable_page", locale = locale))
+}
+
+fn favorites_page(total_notes: &usize, locale: &str) -> String {
+    format!(
+        "{}",
+        t!("favorites_page", locale = locale, total_notes = total_notes)
+    )
+}
+
+fn no_favorites_page(locale: &str) -> String {
+    format!("{}", t!("no_favorites_page", locale = locale))
+}
+
+fn schedule_page(subscription: &Subscription, locale: &str) -> String {
+    match subscription.frequency {
+        Frequency::Daily => format!(
+            "{}",
+            t!(
+                "schedule_page.daily",
+                locale = locale,
+                time = subscription.local_time,
+                timezone = subscription.timezone
+            )
+        ),
+        Frequency::Weekly => format!(
+            "{}",
+            t!(
+                "schedule_page.weekly",
+                locale = locale,
+                weekday = weekday_name(subscription.weekday.unwrap_or_default(), locale),
+                time = subscription.local_time,
+                timezone = subscription.timezone
+            )
+        ),
+    }
+}
+
+// INFO: 0 is monday, as stored in subscriptions
+fn weekday_name(weekday: i64, locale: &str) -> String {
+    let name = match weekday {
+        0 => t!("weekdays.monday", locale = locale),
+        1 => t!("weekdays.tuesday", locale = locale),
+        2 => t!("weekdays.wednesday", locale = locale),
+        3 => t!("weekdays.thursday", locale = locale),
+        4 => t!("weekdays.friday", locale = locale),
+        5 => t!("weekdays.saturday", locale = locale),
+        _ => t!("weekdays.sunday", locale = locale),
+    };
+
+    name.to_string()
+}
+
+fn no_schedule_page(locale: &str) -> String {
+    format!("{}", t!("no_schedule_page", locale = locale))
+}
+
+fn schedule_removed_page(locale: &str) -> String {
+    format!("{}", t!("schedule_removed_page", locale = locale))
+}
+
+fn error_schedule_page(locale: &str) -> String {
+    format!("{}", t!("error_schedule_page", locale = locale))
+}
+
+fn scheduled_note_page(user: &str, caption: Option<&str>, locale: &str) -> String {
+    format!(
+        "{}{}",
+        t!("scheduled_note_page", locale = locale, user = user),
+        note_caption(caption, locale)
+    )
+}
+
+// INFO: captions are typed by authors, pages are parsed as HTML
+fn note_caption(caption: Option<&str>, locale: &str) -> String {
+    match caption {
+        Some(caption) => format!(
+            "\n{}",
+            t!(
+                "note_caption",
+                locale = locale,
+                caption = html::escape(caption)
+            )
+        ),
+        None => String::new(),
+    }
+}
+
+fn invalid_button_page(locale: &str) -> String {
+    format!("{}", t!("invalid_button_page", locale = locale))
+}
+
+// INFO: shown in a callback answer alert, plain text of at most 200 characters
+fn stale_button_alert(locale: &str) -> String {
+    format!("{}", t!("stale_button_alert", locale = locale))
+}
@@ -1,125 +1,78 @@
-use log::{debug, info, warn};
-use reqwest::Client;
+use log::info;
 use serde::{Deserialize, Serialize};
-use teloxide::{requests::ResponseResult, types::Chat};
+use teloxide::{
+    requests::ResponseResult,
+    types::{Chat, User as TelegramUser},
+};
 
-use crate::locale::Locale;
+use cheer_up_core::client;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub struct UserId(i64);
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct User {
-    pub id: i64,
-    pub telegram_id: i64,
-    pub username: String,
-    pub first_name: String,
-    pub last_name: Option<String>,
-    pub locale: Locale,
-}
-
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default)]
-pub struct UpdateUser {
-    id: Option<i64>,
-    telegram_id: Option<i64>,
-    username: Option<String>,
-    first_name: Option<String>,
-    last_name: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NewUser {
-    telegram_id: i64,
-    username: String,
-    first_name: String,
-    last_name: Option<String>,
-    locale: Locale,
-}
+use crate::{config, locale::Locale};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UserBody<T> {
-    pub user: T,
-}
+pub use cheer_up_core::user::{NewUser, User};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UserListBody<T> {
-    pub users: Vec<T>,
-}
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UserId(i64);
 
+/// Registers the telegram account of `user`, or refreshes its names if already registered.
+/// Chats carry no language, prefer [`register_user`] when the sender is known.
 pub async fn save_user_to_db(user: &Chat) -> ResponseResult<User> {
-    let new_user_locale = dotenvy::var("LOCALE").unwrap_or("en".to_string());
+    // INFO: telegram usernames are optional, the numeric id stands in for missing ones
+    let username = user
+        .username()
+        .map(|username| username.to_string())
+        .unwrap_or(user.id.0.to_string());
 
     let new_user = NewUser {
         telegram_id: user.id.0,
-        username: user
-            .username()
-            .unwrap_or(&user.id.0.to_string())
-            .to_string(),
-        first_name: user
-            .username()
-            .unwrap_or(&user.id.0.to_string())
-            .to_string(),
-        last_name: match user.last_name() {
-            Some(name) => Some(name.to_string()),
-            None => None,
-        },
-        locale: Locale::from_str("it"),
+        first_name: user.first_name().unwrap_or(&username).to_string(),
+        username,
+        last_name: user.last_name().map(|name| name.to_string()),
+        locale: config::get().default_locale.clone(),
     };
     info!("[SAVE_USER_TO_DB] new_user is: {:?}", new_user);
 
-    let client = Client::new();
-
-    let resp = client
-        .post("http://0.0.0.0:1989/api/users")
-        .json::<NewUser>(&new_user)
-        .send()
-        .await?;
-    let res_json = resp.json::<UserBody<User>>().await?.user;
+    let res_json = client::global().upsert_user(&new_user).await?;
     info!("resp.json() is: {:#?}", res_json);
 
     Ok(res_json)
 }
 
-pub async fn get_user_by_telegram_id(user: &Chat) -> ResponseResult<User> {
-    let client = Client::new();
+/// Registers the sender of a message, or refreshes its names if already registered.
+/// New users get the language of their telegram app if supported.
+pub async fn register_user(from: &TelegramUser) -> ResponseResult<User> {
+    let default_locale = &config::get().default_locale;
 
-    info!(
-        "[GET_USER_BY_TELEGRAM_ID] username is: {:?}",
-        user.username().unwrap_or("guest")
-    );
-    let vnote_author = client
-        .get(format!(
-            "http://0.0.0.0:1989/api/users/name/{}",
-            user.username().unwrap_or("guest")
-        ))
-        .send()
-        .await?
-        .json::<UserBody<User>>()
-        .await?;
+    let new_user = NewUser {
+        telegram_id: from.id.0 as i64,
+        username: from.username.clone().unwrap_or(from.id.0.to_string()),
+        first_name: from.first_name.clone(),
+        last_name: from.last_name.clone(),
+        locale: Locale::detect(from.language_code.as_deref(), default_locale),
+    };
+    info!("[REGISTER_USER] new_user is: {:?}", new_user);
+
+    let user = client::global().upsert_user(&new_user).await?;
+
+    Ok(user)
+}
+
+pub async fn get_user_by_telegram_id(user: &Chat) -> ResponseResult<User> {
+    info!("[GET_USER_BY_TELEGRAM_ID] telegram_id is: {:?}", user.id.0);
+    let vnote_author = client::global().get_user_by_telegram_id(user.id.0).await?;
     info!(
         "[GET_USER_BY_TELEGRAM_ID] vnote_author.user: {:?}",
-        vnote_author.user
+        vnote_author
     );
 
-    Ok(vnote_author.user)
+    Ok(vnote_author)
 }
 
 pub async fn get_user_by_id(user_id: &i64) -> ResponseResult<User> {
-    let client = Client::new();
-
-    let vnote_author = client
-        .get(format!("http://0.0.0.0:1989/api/users/{}", user_id))
-        .send()
-        .await?
-        .json::<UserBody<User>>()
-        .await?;
-    info!(
-        "[GET_USER_BY_ID] vnote_author.user: {:?}",
-        vnote_author.user
-    );
+    let vnote_author = client::global().get_user(*user_id).await?;
+    info!("[GET_USER_BY_ID] vnote_author.user: {:?}", vnote_author);
 
-    Ok(vnote_author.user)
+    Ok(vnote_author)
 }
 
 pub async fn get_user(chat: &Chat) -> ResponseResult<User> {
@@ -130,19 +83,4 @@ pub async fn get_user(chat: &Chat) -> ResponseResult<User> {
     info!("[GET_USER] user is: {:?}", user);
 
     Ok(user)
-
-    // let client = Client::new();
-    //
-    // let vnote_author = client
-    //     .get(format!("http://0.0.0.0:1989/api/users/{}", user_id))
-    //     .send()
-    //     .await?
-    //     .json::<UserBody<User>>()
-    //     .await?;
-    // info!(
-    //     "[GET_USER_BY_ID] vnote_author.user: {:?}",
-    //     vnote_author.user
-    // );
-    //
-    // Ok(vnote_author.user)
 }
@@ -1,179 +1,271 @@
-use dotenvy as dotenv;
-
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use log::{debug, info, warn};
 use teloxide::{
     net::Download,
+    payloads::SendVideoNoteSetters,
     requests::{Requester, ResponseResult},
-    types::{Chat, VideoNote},
-    Bot,
+    types::{Chat, ChatId, InlineKeyboardMarkup, InputFile, Message, VideoNote},
+    Bot, RequestError,
+};
+
+use cheer_up_core::{
+    client,
+    error::ErrorCode,
+    media::{self, MediaError},
 };
-use tokio::fs;
 
+use crate::config::{self, StorageMode};
 use crate::user::*;
-use crate::utils::{create_user_folder, get_vnote_filename};
+use crate::utils::{get_vnote_filename, get_vnote_media_key};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Note {
-    pub id: i64,
-    pub user_id: i64,
-    pub file_name: String,
-}
+pub use cheer_up_core::note::{
+    NewNote, NewNoteView, Note, NoteBody, NoteListQuery, NotePage, RandomNoteQuery,
+};
+pub use cheer_up_core::reaction::{NewFavorite, NewReaction, NoteReactions, Reaction};
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default)]
-struct DeleteNote {
-    id: Option<i64>,
-    user_id: Option<i64>,
-    file_name: Option<String>,
-}
+/// Downloads the note from telegram into the media store and returns its media key.
+pub async fn upload_vnote(bot: &Bot, videonote: &VideoNote, chat: &Chat) -> ResponseResult<String> {
+    let media_key = get_vnote_media_key(chat, videonote);
+    info!("[UPLOAD_VNOTE] storing note as: {}", media_key);
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default)]
-struct UpdateNote {
-    id: Option<i64>,
-    user_id: Option<i64>,
-    file_name: Option<String>,
-}
+    let vnote_file = bot.get_file(&videonote.file.id).await?;
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-pub struct NewNote {
-    user_id: i64,
-    file_name: String,
-}
+    let mut bytes: Vec<u8> = Vec::new();
+    bot.download_file(&vnote_file.path, &mut bytes).await?;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NoteBody<T> {
-    pub note: T,
-}
+    media::global().put(&media_key, bytes).await?;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NoteListBody<T> {
-    pub notes: Vec<T>,
+    Ok(media_key)
 }
 
-pub async fn upload_vnote(bot: &Bot, videonote: &VideoNote, chat: &Chat) -> ResponseResult<()> {
-    let folder_path = create_user_folder(&chat).await?;
-    println!("created user folder path is: {}", folder_path);
-
-    let vnote_file = bot.get_file(&videonote.file.id).await?;
-    let vnote_out_path = format!("{}/{}", folder_path, get_vnote_filename(&videonote));
-
-    let mut output_file = fs::File::create(&vnote_out_path).await?;
-
-    bot.download_file(&vnote_file.path, &mut output_file)
-        .await?;
+/// Random note shared with the groups of `user_id`, `None` if there is none.
+/// With `personal` only notes addressed to `user_id` are picked.
+pub async fn get_random_vnote(user_id: &i64, personal: bool) -> ResponseResult<Option<Note>> {
+    let query = RandomNoteQuery {
+        for_user: Some(*user_id),
+        personal,
+        ..Default::default()
+    };
 
-    Ok(())
+    match client::global().random_note(&query).await {
+        Ok(vnote) => Ok(Some(vnote)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
 }
 
-pub async fn get_random_vnote(bot: &Bot, chat: &Chat) -> ResponseResult<Note> {
-    let client = Client::new();
-
-    let vnote = client
-        .get(format!("http://0.0.0.0:1989/api/notes/random",))
-        .send()
-        .await?
-        .json::<NoteBody<Note>>()
-        .await?;
+/// Sends a stored note, by its telegram `file_id` in file_id mode, falling back
+/// to the media store copy if the note has no id or telegram rejects it.
+pub async fn send_vnote(
+    bot: &Bot,
+    chat_id: ChatId,
+    vnote: &Note,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> ResponseResult<Message> {
+    // INFO: notes saved before every note had a store copy can only be sent by id
+    let by_file_id = config::get().storage_mode == StorageMode::FileId || vnote.media_key.is_none();
+
+    if let Some(file_id) = vnote.file_id.as_ref().filter(|_| by_file_id) {
+        let mut request = bot.send_video_note(chat_id, InputFile::file_id(file_id));
+        if let Some(keyboard) = keyboard.clone() {
+            request = request.reply_markup(keyboard);
+        }
+
+        match request.await {
+            Ok(sent) => return Ok(sent),
+            Err(RequestError::Api(e)) => {
+                warn!(
+                    "[SEND_VNOTE] file_id of note {} rejected ({}), sending stored copy",
+                    vnote.id, e
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let Some(media_key) = &vnote.media_key else {
+        return Err(MediaError::NotFound(format!("note {}", vnote.id)).into());
+    };
 
-    Ok(vnote.note)
+    info!("[SEND_VNOTE] sending note {} from {}", vnote.id, media_key);
+    let bytes = media::global().get(media_key).await?;
+    let mut request = bot.send_video_note(
+        chat_id,
+        InputFile::memory(bytes).file_name(vnote.file_name.clone()),
+    );
+    if let Some(keyboard) = keyboard {
+        request = request.reply_markup(keyboard);
+    }
+
+    request.await
 }
 
-pub async fn save_vnote_to_db(vnote: &str, author: &Chat) -> ResponseResult<()> {
-    let client = Client::new();
-
+pub async fn save_vnote_to_db(
+    vnote: &VideoNote,
+    author: &Chat,
+    media_key: String,
+) -> ResponseResult<()> {
     let vnote_author = match get_user_by_telegram_id(author).await {
         Ok(vnote) => vnote,
         Err(_) => save_user_to_db(&author).await?,
     };
 
-    let output_file_name = format!("{}.mpeg", vnote);
     let new_note = NewNote {
         user_id: vnote_author.id,
-        file_name: output_file_name,
+        file_name: get_vnote_filename(vnote),
+        file_id: Some(vnote.file.id.clone()),
+        file_unique_id: Some(vnote.file.unique_id.clone()),
+        duration: Some(vnote.duration.into()),
+        file_size: Some(vnote.file.size.into()),
+        media_key: Some(media_key),
+        // INFO: shared with every group of the author
+        group_id: None,
+        recipient_id: None,
+        caption: None,
     };
-    println!("new_note is: {:#?}", new_note);
-
-    let resp = client
-        .post("http://0.0.0.0:1989/api/notes")
-        .json::<NewNote>(&new_note)
-        .send()
-        .await?;
+    debug!("[SAVE_VNOTE_TO_DB] new_note is: {:?}", new_note);
 
-    println!("resp is: {:#?}", resp);
+    client::global().create_note(&new_note).await?;
 
     Ok(())
 }
 
-pub async fn get_author_vnote_list_from_db(author: &Chat) -> ResponseResult<Vec<Note>> {
-    let client = Client::new();
-
+pub async fn get_vnote_list_from_db(author: &Chat) -> ResponseResult<Vec<Note>> {
     let vnote_author = match get_user_by_telegram_id(author).await {
         Ok(user) => user,
         Err(_) => save_user_to_db(&author).await?,
     };
 
-    let vnote_list = client
-        .get(format!(
-            "http://0.0.0.0:1989/api/notes/user/{}",
-            vnote_author.id
-        ))
-        .send()
-        .await?
-        .json::<NoteListBody<Note>>()
-        .await?;
+    let query = NoteListQuery {
+        for_user: Some(vnote_author.id),
+        ..Default::default()
+    };
+    let vnote_list = client::global().list_notes(&query).await?;
 
-    Ok(vnote_list.notes)
+    Ok(vnote_list)
 }
 
-pub async fn get_vnote_list_from_db(author: &Chat) -> ResponseResult<Vec<Note>> {
-    let client = Client::new();
+/// Notes listed on each `/list` page.
+pub const LIST_PAGE_SIZE: i64 = 5;
+
+/// A `/list` page of the notes shared with `user_id`, `after` and `before` are
+/// cursors of a previous [`NotePage`], the first page if both are `None`.
+pub async fn get_vnote_page_from_db(
+    user_id: &i64,
+    after: Option<i64>,
+    before: Option<i64>,
+) -> ResponseResult<NotePage> {
+    let query = NoteListQuery {
+        for_user: Some(*user_id),
+        after,
+        before,
+        limit: Some(LIST_PAGE_SIZE),
+        ..Default::default()
+    };
+    let vnote_page = client::global().list_notes_page(&query).await?;
 
-    let vnote_author = match get_user_by_telegram_id(author).await {
-        Ok(user) => user,
-        Err(_) => save_user_to_db(&author).await?,
+    Ok(vnote_page)
+}
+
+pub async fn get_vnote(vnote_id: &i64) -> ResponseResult<Note> {
+    let vnote = client::global().get_note(*vnote_id).await?;
+
+    Ok(vnote)
+}
+
+/// Same as [`get_vnote`], `None` if the note was deleted in the meantime.
+pub async fn find_vnote(vnote_id: &i64) -> ResponseResult<Option<Note>> {
+    match client::global().get_note(*vnote_id).await {
+        Ok(vnote) => Ok(Some(vnote)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Remembers that `user_id` received the note, so it is not picked again before the others.
+pub async fn record_vnote_view(vnote_id: &i64, user_id: &i64) -> ResponseResult<()> {
+    let view = NewNoteView { user_id: *user_id };
+    client::global().record_note_view(*vnote_id, &view).await?;
+
+    Ok(())
+}
+
+/// Reacts to a received note, `None` if the note is not shared with the user.
+pub async fn react_to_vnote(
+    vnote_id: &i64,
+    user_id: &i64,
+    reaction: Reaction,
+) -> ResponseResult<Option<NoteReactions>> {
+    let new_reaction = NewReaction {
+        user_id: *user_id,
+        reaction,
     };
 
-    let vnote_list = client
-        .get(format!("http://0.0.0.0:1989/api/notes"))
-        .send()
-        .await?
-        .json::<NoteListBody<Note>>()
-        .await?;
+    match client::global()
+        .react_to_note(*vnote_id, &new_reaction)
+        .await
+    {
+        Ok(reactions) => Ok(Some(reactions)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
 
-    Ok(vnote_list.notes)
+/// Saves a received note to favorites, `None` if the note is not shared with the user.
+pub async fn save_favorite_vnote(vnote_id: &i64, user_id: &i64) -> ResponseResult<Option<Note>> {
+    let favorite = NewFavorite { note_id: *vnote_id };
+
+    match client::global()
+        .add_user_favorite(*user_id, &favorite)
+        .await
+    {
+        Ok(vnote) => Ok(Some(vnote)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
 }
 
-pub async fn delete_vnote_from_db(vnote_id: &i64) -> ResponseResult<NoteBody<String>> {
-    let client = Client::new();
+pub async fn get_favorite_vnotes(user_id: &i64) -> ResponseResult<Vec<Note>> {
+    let vnote_list = client::global().list_user_favorites(*user_id).await?;
 
-    let _deleted_vnote = client
-        .delete(format!("http://0.0.0.0:1989/api/notes/{}", vnote_id))
-        .send()
-        .await?
-        .json::<NoteBody<String>>()
-        .await?;
+    Ok(vnote_list)
+}
+
+/// Number of notes friends addressed to `user_id`.
+pub async fn count_personal_vnotes(user_id: &i64) -> ResponseResult<usize> {
+    let query = NoteListQuery {
+        for_user: Some(*user_id),
+        personal: true,
+        limit: Some(1),
+        ..Default::default()
+    };
+    let vnote_page = client::global().list_notes_page(&query).await?;
 
-    Ok(_deleted_vnote)
+    Ok(vnote_page.total as usize)
 }
 
-pub async fn delete_all_user_vnotes(author: &Chat) -> ResponseResult<()> {
-    let client = Client::new();
+/// Deletes a note on behalf of `user_id`, the api refuses unless they own it or are an admin.
+/// Refusals are returned as their [`ErrorCode`] so that callers can explain them.
+pub async fn delete_vnote_from_db(
+    vnote_id: &i64,
+    user_id: &i64,
+) -> ResponseResult<Result<NoteBody<String>, ErrorCode>> {
+    match client::global().delete_note(*vnote_id, *user_id).await {
+        Ok(deleted_vnote) => Ok(Ok(NoteBody {
+            note: deleted_vnote,
+        })),
+        Err(e) if e.is_client_error() => Ok(Err(e.code().unwrap_or(ErrorCode::Unknown))),
+        Err(e) => Err(e.into()),
+    }
+}
 
+pub async fn delete_all_user_vnotes(author: &Chat) -> ResponseResult<()> {
     let vnote_author = match get_user_by_telegram_id(author).await {
         Ok(user) => user,
         Err(_) => save_user_to_db(&author).await?,
     };
 
-    let _deleted_vnote = client
-        .delete(format!(
-            "http://0.0.0.0:1989/api/notes/user/{}",
-            vnote_author.id
-        ))
-        .send()
-        .await?
-        .json::<NoteBody<String>>()
+    let _deleted_vnote = client::global()
+        .delete_user_notes(vnote_author.id, vnote_author.id)
         .await?;
 
     Ok(())